@@ -0,0 +1,863 @@
+use std::collections::BTreeMap;
+
+use chrono::{Days, Local, NaiveDate, NaiveDateTime, NaiveTime, TimeDelta};
+use serde::{Deserialize, Serialize};
+
+use crate::auth::{Credentials, Role};
+use crate::currency::{Currency, CurrencyInfo, CurrencyRegistry};
+use crate::error::{Action, BankError};
+use crate::interest::{self, DEFAULT_WITHHOLDING_TAX_RATE, InterestSettings};
+use crate::money::Money;
+use crate::provider::{ProviderConfig, RateProvider};
+use crate::fees::{FeeRule, FeeSchedule, IncomeEntry, IncomeKind};
+use crate::import::{RateRow, RowError};
+use crate::rates::{Conversion, DEFAULT_APPROVAL_THRESHOLD, DEFAULT_MAX_RATE_MOVE, MAX_APPROVAL_THRESHOLD, PairRate, RateTable, format_rate};
+use crate::transaction::{Transaction, TransactionKind};
+
+const FIRST_ACCOUNT_NUMBER: u64 = 100_001;
+const FIRST_TRANSACTION_ID: u64 = 1;
+const FIRST_PENDING_RATE_ID: u64 = 1;
+// Administrators the bank keeps, so one can approve what another enters
+const MIN_ADMINISTRATORS: usize = 2;
+// Highest balance an account may hold, in the major units of its currency
+pub const MAX_BALANCE: i64 = 1_000_000;
+
+// Balance held in one currency
+#[derive(Serialize, Deserialize, Clone, Copy)]
+pub struct Wallet {
+    pub balance: Money,
+    // Unposted gross interest in major units, kept unrounded until it is posted
+    #[serde(default)]
+    pub accrued_interest: f64,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(from = "StoredAccount")]
+pub struct Account {
+    pub number: u64,
+    pub name: String,
+    // One wallet per currency the account has held, keyed by currency
+    pub wallets: BTreeMap<Currency, Wallet>,
+    pub interest: InterestSettings,
+    // None for accounts opened before PINs were introduced
+    pub pin: Option<Credentials>,
+    pub role: Role,
+}
+
+// Account as saved, which may still have the single balance used before accounts held wallets
+#[derive(Deserialize)]
+struct StoredAccount {
+    number: u64,
+    name: String,
+    #[serde(default)]
+    wallets: BTreeMap<Currency, Wallet>,
+    balance: Option<Money>,
+    #[serde(default)]
+    interest: InterestSettings,
+    #[serde(default)]
+    accrued_interest: f64,
+    #[serde(default)]
+    pin: Option<Credentials>,
+    #[serde(default)]
+    role: Role,
+}
+
+impl From<StoredAccount> for Account {
+    fn from(stored: StoredAccount) -> Account {
+        let mut wallets = stored.wallets;
+
+        if let Some(balance) = stored.balance {
+            wallets.insert(balance.currency(), Wallet { balance, accrued_interest: stored.accrued_interest });
+        }
+
+        Account { number: stored.number, name: stored.name, wallets, interest: stored.interest, pin: stored.pin, role: stored.role }
+    }
+}
+
+impl Account {
+    pub fn wallet(&self, currency: Currency) -> Option<&Wallet> {
+        self.wallets.get(&currency)
+    }
+
+    // Opens an empty wallet the first time a currency is used
+    pub fn wallet_mut(&mut self, currency: &CurrencyInfo) -> &mut Wallet {
+        self.wallets.entry(currency.code).or_insert(Wallet { balance: Money::zero(currency), accrued_interest: 0.0 })
+    }
+
+    // Balance in a currency, zero when the account has no wallet in it
+    pub fn balance(&self, currency: &CurrencyInfo) -> Money {
+        self.wallet(currency.code).map_or(Money::zero(currency), |w| w.balance)
+    }
+
+    pub fn has_funds(&self) -> bool {
+        self.wallets.values().any(|w| w.balance.is_positive())
+    }
+}
+
+// Terms offered for an exchange, valid until the rate table next changes
+pub struct ExchangeQuote {
+    pub account: u64,
+    // Debited from the source wallet, fee included
+    pub amount: Money,
+    pub fee: Money,
+    pub conversion: Conversion,
+    pub credit: Money,
+    // Kept by the bank in the target currency by converting at the customer rate instead of the mid rate
+    pub spread: Money,
+    rates_version: u64,
+}
+
+// Rate change held back until a second administrator approves it
+#[derive(Serialize, Deserialize, Clone)]
+pub struct PendingRate {
+    pub id: u64,
+    pub from: Currency,
+    pub to: Currency,
+    pub rate: PairRate,
+    pub entered_by: String,
+    // Administrator whose entry, import or refresh produced the change
+    pub requested_by: u64,
+    pub requested_at: NaiveDateTime,
+}
+
+// What recording a set of rates did: rates changed, changes waiting for approval and rows rejected
+pub struct RateUpdate {
+    pub changed: usize,
+    pub pending: Vec<u64>,
+    pub rejected: Vec<RowError>,
+}
+
+// Registry of all open accounts keyed by their generated account number
+#[derive(Serialize, Deserialize)]
+pub struct Bank {
+    accounts: BTreeMap<u64, Account>,
+    next_account_number: u64,
+    pub rates: RateTable,
+    #[serde(default)]
+    transactions: Vec<Transaction>,
+    #[serde(default = "first_transaction_id")]
+    next_transaction_id: u64,
+    #[serde(default = "default_withholding_tax_rate")]
+    pub withholding_tax_rate: f64,
+    // Exchanges refuse to use a rate not recorded or confirmed within this many hours
+    #[serde(default)]
+    pub max_rate_age_hours: Option<u32>,
+    #[serde(default)]
+    pub rate_provider: ProviderConfig,
+    // Share of the previous mid rate a new rate may move by before it is flagged
+    #[serde(default = "default_max_rate_move")]
+    pub max_rate_move: f64,
+    // Share of the previous mid rate a new rate may move by before a second administrator has to approve it
+    #[serde(default = "default_approval_threshold")]
+    approval_threshold: f64,
+    #[serde(default)]
+    pending_rates: Vec<PendingRate>,
+    #[serde(default = "first_pending_rate_id")]
+    next_pending_rate_id: u64,
+    // Rate each pair was last approved at, or had before changes within the threshold began. New rates are measured
+    // against it as well as the current rate, so steps just under the threshold cannot add up past it.
+    #[serde(default)]
+    approved_rates: BTreeMap<Currency, BTreeMap<Currency, PairRate>>,
+    // Accounts an administrator asked to promote, with who asked, until a second administrator agrees
+    #[serde(default)]
+    pending_promotions: BTreeMap<u64, u64>,
+    #[serde(default = "FeeSchedule::new")]
+    pub fees: FeeSchedule,
+    // Fee and spread revenue, one entry per transaction it was earned on
+    #[serde(default)]
+    income: Vec<IncomeEntry>,
+    // Virtual clock; only moves when end-of-day processing is run
+    #[serde(default = "today")]
+    business_date: NaiveDate,
+}
+
+fn first_transaction_id() -> u64 {
+    FIRST_TRANSACTION_ID
+}
+
+fn default_withholding_tax_rate() -> f64 {
+    DEFAULT_WITHHOLDING_TAX_RATE
+}
+
+fn default_max_rate_move() -> f64 {
+    DEFAULT_MAX_RATE_MOVE
+}
+
+fn default_approval_threshold() -> f64 {
+    DEFAULT_APPROVAL_THRESHOLD
+}
+
+fn first_pending_rate_id() -> u64 {
+    FIRST_PENDING_RATE_ID
+}
+
+fn today() -> NaiveDate {
+    Local::now().date_naive()
+}
+
+// Account names may only contain letters and spaces
+pub fn valid_name(name: &str) -> bool {
+    !name.is_empty() && name.chars().all(|c| c.is_alphabetic() || c == ' ')
+}
+
+impl Default for Bank {
+    fn default() -> Bank {
+        Bank::new()
+    }
+}
+
+impl Bank {
+    pub fn new() -> Bank {
+        let mut rates: RateTable = RateTable::new();
+        for (code, rate) in [("USD", 0.017), ("JPY", 2.6), ("GBP", 0.013), ("EUR", 0.015), ("CNY", 0.12)] {
+            rates.set(Currency::PHP, Currency::new(code).unwrap(), PairRate { buy: rate, sell: rate });
+        }
+
+        Bank {
+            accounts: BTreeMap::new(),
+            next_account_number: FIRST_ACCOUNT_NUMBER,
+            rates,
+            transactions: Vec::new(),
+            next_transaction_id: FIRST_TRANSACTION_ID,
+            withholding_tax_rate: DEFAULT_WITHHOLDING_TAX_RATE,
+            max_rate_age_hours: None,
+            rate_provider: ProviderConfig::Manual,
+            max_rate_move: DEFAULT_MAX_RATE_MOVE,
+            approval_threshold: DEFAULT_APPROVAL_THRESHOLD,
+            pending_rates: Vec::new(),
+            next_pending_rate_id: FIRST_PENDING_RATE_ID,
+            approved_rates: BTreeMap::new(),
+            pending_promotions: BTreeMap::new(),
+            fees: FeeSchedule::new(),
+            income: Vec::new(),
+            business_date: today(),
+        }
+    }
+
+    // Opens a new account with an empty wallet in the given currency and returns its account number.
+    // The first two accounts opened in a new bank become its administrators, so the bank can be set up.
+    pub fn open(&mut self, name: String, pin: &str, currency: &CurrencyInfo) -> Result<u64, BankError> {
+        let role = if self.setting_up() && self.administrators() == self.accounts.len() { Role::Administrator } else { Role::Teller };
+
+        self.open_as(name, pin, currency, role)
+    }
+
+    // Data saved before roles has accounts but no administrator, and none of them can be trusted with the role,
+    // as accounts saved before PINs can be claimed by anyone. A new administrator account is opened instead, once.
+    pub fn set_up_administrator(&mut self, name: String, pin: &str, currency: &CurrencyInfo) -> Result<u64, BankError> {
+        if !self.setting_up() {
+            return Err(BankError::AdministratorExists);
+        }
+
+        self.open_as(name, pin, currency, Role::Administrator)
+    }
+
+    fn open_as(&mut self, name: String, pin: &str, currency: &CurrencyInfo, role: Role) -> Result<u64, BankError> {
+        if !valid_name(&name) {
+            return Err(BankError::InvalidName);
+        }
+        let pin = Credentials::new(pin)?;
+
+        let number = self.next_account_number;
+        let mut account = Account { number, name, wallets: BTreeMap::new(), interest: InterestSettings::default(), pin: Some(pin), role };
+
+        account.wallet_mut(currency);
+        self.accounts.insert(number, account);
+        self.next_account_number += 1;
+
+        Ok(number)
+    }
+
+    // Only an account without money left in it can be closed. Account numbers are never reused.
+    pub fn close(&mut self, number: u64) -> Result<Account, BankError> {
+        match self.accounts.get(&number) {
+            None => Err(BankError::AccountNotFound(number)),
+            Some(account) if account.has_funds() => Err(BankError::HasFunds),
+            Some(account) if account.role == Role::Administrator && self.administrators() <= MIN_ADMINISTRATORS => Err(BankError::LastAdministrator),
+            Some(_) => {
+                self.pending_promotions.remove(&number);
+                Ok(self.accounts.remove(&number).unwrap())
+            }
+        }
+    }
+
+    // Checks the PIN of an account against the wall clock, as lockouts run in real time, not business days
+    pub fn log_in(&mut self, number: u64, pin: &str) -> Result<(), BankError> {
+        let account = self.accounts.get_mut(&number).ok_or(BankError::AccountNotFound(number))?;
+
+        account.pin.as_mut().ok_or(BankError::NoPin(number))?.verify(pin, Local::now().naive_local())
+    }
+
+    // Sets or resets the PIN of an account, clearing any lockout. Only an administrator may, so accounts saved
+    // before PINs cannot be claimed by whoever types their number first.
+    pub fn set_pin(&mut self, admin: u64, number: u64, pin: &str) -> Result<(), BankError> {
+        self.require_administrator(admin)?;
+        let account = self.accounts.get_mut(&number).ok_or(BankError::AccountNotFound(number))?;
+
+        account.pin = Some(Credentials::new(pin)?);
+        Ok(())
+    }
+
+    fn administrators(&self) -> usize {
+        self.accounts.values().filter(|a| a.role == Role::Administrator).count()
+    }
+
+    // Until the bank has two administrators, nobody could approve what the only one enters, so it is still being
+    // set up: new banks make their first accounts administrators, and data saved before roles sets them up
+    pub fn setting_up(&self) -> bool {
+        self.administrators() < MIN_ADMINISTRATORS
+    }
+
+    pub fn require_administrator(&self, number: u64) -> Result<(), BankError> {
+        match self.accounts.get(&number) {
+            None => Err(BankError::AccountNotFound(number)),
+            Some(account) if account.role != Role::Administrator => Err(BankError::AdministratorOnly),
+            Some(_) => Ok(()),
+        }
+    }
+
+    // A promotion to administrator only takes effect once a second administrator asks for it too, so nobody can
+    // promote an account of their own to approve their own changes. Returns false while a promotion waits.
+    pub fn set_role(&mut self, admin: u64, number: u64, role: Role) -> Result<bool, BankError> {
+        self.require_administrator(admin)?;
+        let account = self.accounts.get(&number).ok_or(BankError::AccountNotFound(number))?;
+
+        if role == Role::Administrator {
+            if account.role == Role::Administrator {
+                return Ok(true);
+            }
+            match self.pending_promotions.get(&number) {
+                Some(&requested_by) if requested_by == admin => return Err(BankError::SecondApproverRequired),
+                Some(_) => {}
+                None => {
+                    self.pending_promotions.insert(number, admin);
+                    return Ok(false);
+                }
+            }
+        } else if account.role == Role::Administrator && self.administrators() <= MIN_ADMINISTRATORS {
+            return Err(BankError::LastAdministrator);
+        }
+
+        self.pending_promotions.remove(&number);
+        self.accounts.get_mut(&number).unwrap().role = role;
+        Ok(true)
+    }
+
+    // Administrator who asked to promote an account, while the promotion waits for a second one
+    pub fn pending_promotion(&self, number: u64) -> Option<u64> {
+        self.pending_promotions.get(&number).copied()
+    }
+
+    pub fn get(&self, number: u64) -> Option<&Account> {
+        self.accounts.get(&number)
+    }
+
+    pub fn get_mut(&mut self, number: u64) -> Option<&mut Account> {
+        self.accounts.get_mut(&number)
+    }
+
+    pub fn accounts(&self) -> impl Iterator<Item = &Account> {
+        self.accounts.values()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.accounts.is_empty()
+    }
+
+    // Checks that every currency held, rated or charged a fee in is registered with the minor units
+    // its amounts were saved in, so a hand-edited registry or data file is reported instead of panicking
+    pub fn check_currencies(&self, registry: &CurrencyRegistry) -> Result<(), String> {
+        let registered = |code: Currency| registry.get(code).ok_or(format!("currency {code} is not registered"));
+        let same_units = |amount: Money| {
+            let info = registered(amount.currency())?;
+            if info.minor_units != amount.minor_units() {
+                return Err(format!("{} amounts have {} minor units but the registry has {}", info.code, amount.minor_units(), info.minor_units));
+            }
+            Ok(())
+        };
+
+        for account in self.accounts.values() {
+            for (code, wallet) in &account.wallets {
+                registered(*code)?;
+                same_units(wallet.balance)?;
+            }
+        }
+        for (from, to, _) in self.rates.pairs() {
+            registered(from)?;
+            registered(to)?;
+        }
+        for pending in &self.pending_rates {
+            registered(pending.from)?;
+            registered(pending.to)?;
+        }
+        for (code, rule) in self.fees.rules() {
+            registered(code)?;
+            if let FeeRule::Flat(fee) = rule {
+                same_units(fee)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn business_date(&self) -> NaiveDate {
+        self.business_date
+    }
+
+    // Time of day on the business date
+    pub fn now(&self) -> NaiveDateTime {
+        self.business_date.and_time(Local::now().time())
+    }
+
+    // Appends a transaction dated on the current business date and returns its ID
+    pub fn record(&mut self, account: Option<u64>, kind: TransactionKind) -> u64 {
+        self.record_at(account, self.now(), kind)
+    }
+
+    fn record_at(&mut self, account: Option<u64>, timestamp: NaiveDateTime, kind: TransactionKind) -> u64 {
+        let id = self.next_transaction_id;
+
+        self.transactions.push(Transaction { id, timestamp, account, kind });
+        self.next_transaction_id += 1;
+
+        id
+    }
+
+    pub fn transaction(&self, id: u64) -> Option<&Transaction> {
+        self.transactions.iter().find(|t| t.id == id)
+    }
+
+    // Adds cash to one of an account's wallets and returns the ID of the deposit
+    pub fn deposit(&mut self, number: u64, amount: Money, currency: &CurrencyInfo) -> Result<u64, BankError> {
+        let account = self.accounts.get_mut(&number).ok_or(BankError::AccountNotFound(number))?;
+
+        if !amount.is_positive() {
+            return Err(BankError::NotPositive(Action::Deposit));
+        } else if account.balance(currency).checked_add(amount).is_none_or(|after| after > Money::from_major(MAX_BALANCE, currency)) {
+            return Err(BankError::CapExceeded { account: number, currency: currency.code, action: Action::Deposit });
+        }
+
+        let wallet = account.wallet_mut(currency);
+        let before = wallet.balance;
+        wallet.balance += amount;
+        let after = wallet.balance;
+
+        Ok(self.record(Some(number), TransactionKind::Deposit { amount, before, after }))
+    }
+
+    // Pays cash out of one of an account's wallets and returns the ID of the withdrawal
+    pub fn withdraw(&mut self, number: u64, amount: Money) -> Result<u64, BankError> {
+        let account = self.accounts.get_mut(&number).ok_or(BankError::AccountNotFound(number))?;
+
+        if !amount.is_positive() {
+            return Err(BankError::NotPositive(Action::Withdrawal));
+        }
+
+        let wallet = match account.wallets.get_mut(&amount.currency()) {
+            Some(wallet) if amount <= wallet.balance => wallet,
+            _ => return Err(BankError::InsufficientFunds { currency: amount.currency(), action: Action::Withdrawal }),
+        };
+        let before = wallet.balance;
+        wallet.balance -= amount;
+        let after = wallet.balance;
+
+        Ok(self.record(Some(number), TransactionKind::Withdrawal { amount, before, after }))
+    }
+
+    // Accrues a day of interest on every wallet for each calendar day up to the next business day,
+    // posting accrued interest at the end of each account's compounding period.
+    // Returns the IDs of the interest postings.
+    pub fn end_of_day(&mut self, registry: &CurrencyRegistry) -> Vec<u64> {
+        let next_business_date = interest::next_business_day(self.business_date);
+        let mut postings: Vec<(u64, NaiveDate, TransactionKind)> = Vec::new();
+        let mut date = self.business_date;
+
+        while date < next_business_date {
+            let next_date = date + Days::new(1);
+
+            for account in self.accounts.values_mut() {
+                let settings = account.interest;
+
+                for wallet in account.wallets.values_mut() {
+                    wallet.accrued_interest += wallet.balance.to_f64() * settings.annual_rate
+                        * settings.day_count.year_fraction(date, next_date);
+
+                    let gross = Money::from_f64(wallet.accrued_interest, registry.info(wallet.balance.currency()));
+                    if settings.compounding.is_posting_day(date) && gross.is_positive() {
+                        let (tax, net) = interest::withhold(gross, self.withholding_tax_rate);
+                        let before = wallet.balance;
+
+                        wallet.balance += net;
+                        // Keep the rounding remainder so it is not lost or posted twice
+                        wallet.accrued_interest -= gross.to_f64();
+                        postings.push((account.number, date, TransactionKind::Interest { gross, tax, before, after: wallet.balance }));
+                    }
+                }
+            }
+
+            date = next_date;
+        }
+
+        self.business_date = next_business_date;
+
+        postings.into_iter()
+            .map(|(number, date, kind)| self.record_at(Some(number), date.and_time(NaiveTime::from_hms_opt(23, 59, 59).unwrap()), kind))
+            .collect()
+    }
+
+    // Moves an amount from one of an account's wallets into another account's wallet in the given currency,
+    // converting it when the currencies differ. Returns the IDs of the debit and credit transactions.
+    pub fn transfer(&mut self, from: u64, to: u64, amount: Money, to_currency: &CurrencyInfo) -> Result<(u64, u64), BankError> {
+        if from == to {
+            return Err(BankError::SameAccount);
+        }
+
+        let (credit, conversion) = self.check_move(from, to, amount, None, to_currency, Action::Transfer)?;
+        let spread = amount.convert(to_currency, conversion.mid_rate) - credit;
+        let timestamp = self.now();
+        let debit_id = self.next_transaction_id;
+        let credit_id = debit_id + 1;
+        let ((before, after), (credit_before, credit_after)) = self.apply_move(from, to, amount, credit, to_currency);
+
+        self.record_at(Some(from), timestamp, TransactionKind::TransferOut { amount, before, after, to, linked: credit_id });
+        self.record_at(Some(to), timestamp, TransactionKind::TransferIn {
+            amount: credit, before: credit_before, after: credit_after, from, linked: debit_id, rate: conversion.rate,
+        });
+        self.earn(timestamp, credit_id, IncomeKind::Spread, spread);
+
+        Ok((debit_id, credit_id))
+    }
+
+    // Works out what exchanging an amount from one of an account's wallets into another currency would
+    // cost and credit, without changing anything
+    pub fn quote_exchange(&self, number: u64, amount: Money, to_currency: &CurrencyInfo) -> Result<ExchangeQuote, BankError> {
+        if amount.currency() == to_currency.code {
+            return Err(BankError::SameCurrency);
+        }
+
+        let fee = self.fees.fee(amount);
+        let (credit, conversion) = self.check_move(number, number, amount, Some(fee), to_currency, Action::Exchange)?;
+        let spread = (amount - fee).convert(to_currency, conversion.mid_rate) - credit;
+
+        Ok(ExchangeQuote { account: number, amount, fee, conversion, credit, spread, rates_version: self.rates.version() })
+    }
+
+    // Carries out a quoted exchange, refusing it when the rates have changed since it was quoted.
+    // Returns the IDs of the debit and credit transactions.
+    pub fn exchange(&mut self, quote: &ExchangeQuote, to_currency: &CurrencyInfo) -> Result<(u64, u64), BankError> {
+        if quote.rates_version != self.rates.version() {
+            return Err(BankError::QuoteExpired);
+        }
+
+        let number = quote.account;
+        let (amount, fee, spread) = (quote.amount, quote.fee, quote.spread);
+        let (credit, conversion) = self.check_move(number, number, amount, Some(fee), to_currency, Action::Exchange)?;
+        let timestamp = self.now();
+        let debit_id = self.next_transaction_id;
+        let credit_id = debit_id + 1;
+        let ((before, after), (credit_before, credit_after)) = self.apply_move(number, number, amount, credit, to_currency);
+
+        self.record_at(Some(number), timestamp, TransactionKind::ExchangeOut {
+            amount, before, after, to: to_currency.code, linked: credit_id, fee: Some(fee),
+        });
+        self.record_at(Some(number), timestamp, TransactionKind::ExchangeIn {
+            amount: credit, before: credit_before, after: credit_after, rate: conversion.rate, linked: debit_id,
+        });
+        self.earn(timestamp, debit_id, IncomeKind::ExchangeFee, fee);
+        self.earn(timestamp, credit_id, IncomeKind::Spread, spread);
+
+        Ok((debit_id, credit_id))
+    }
+
+    // Checks that an amount can leave the sending wallet and, less the fee, be converted into the receiving
+    // one without breaking either balance limit. Returns the amount to credit and the conversion used.
+    fn check_move(&self, from: u64, to: u64, amount: Money, fee: Option<Money>, to_currency: &CurrencyInfo, action: Action) -> Result<(Money, Conversion), BankError> {
+        let source = self.get(from).ok_or(BankError::AccountNotFound(from))?;
+        let destination = self.get(to).ok_or(BankError::AccountNotFound(to))?;
+        let from_currency = amount.currency();
+
+        if !amount.is_positive() {
+            return Err(BankError::NotPositive(action));
+        } else if source.wallet(from_currency).is_none_or(|w| amount > w.balance) {
+            return Err(BankError::InsufficientFunds { currency: from_currency, action });
+        }
+
+        let conversion = if from_currency == to_currency.code {
+            Conversion { rate: 1.0, mid_rate: 1.0, path: vec![from_currency, to_currency.code] }
+        } else {
+            let conversion = self.rates.quote(from_currency, to_currency.code)
+                .ok_or(BankError::NoRate { from: from_currency, to: to_currency.code })?;
+
+            self.check_rate_age(&conversion)?;
+            conversion
+        };
+        let credit = fee.map_or(amount, |fee| amount - fee).convert(to_currency, conversion.rate);
+
+        if !credit.is_positive() {
+            return Err(BankError::AmountTooSmall(to_currency.code));
+        } else if destination.balance(to_currency).checked_add(credit).is_none_or(|after| after > Money::from_major(MAX_BALANCE, to_currency)) {
+            return Err(BankError::CapExceeded { account: to, currency: to_currency.code, action });
+        }
+
+        Ok((credit, conversion))
+    }
+
+    fn earn(&mut self, timestamp: NaiveDateTime, transaction: u64, kind: IncomeKind, amount: Money) {
+        if amount.is_positive() {
+            self.income.push(IncomeEntry { timestamp, transaction, kind, amount });
+        }
+    }
+
+    pub fn income(&self) -> &[IncomeEntry] {
+        &self.income
+    }
+
+    // Debits one wallet and credits another, returning each wallet's balance before and after
+    fn apply_move(&mut self, from: u64, to: u64, amount: Money, credit: Money, to_currency: &CurrencyInfo) -> ((Money, Money), (Money, Money)) {
+        let source = self.accounts.get_mut(&from).unwrap().wallets.get_mut(&amount.currency()).unwrap();
+        let debit = (source.balance, source.balance - amount);
+        source.balance -= amount;
+
+        let destination = self.accounts.get_mut(&to).unwrap().wallet_mut(to_currency);
+        let credit = (destination.balance, destination.balance + credit);
+        destination.balance = credit.1;
+
+        (debit, credit)
+    }
+
+    // Records a new rate for a pair and returns the ID of the rate change. How old a rate is counts in real time,
+    // like PIN lockouts, as running end-of-day processing does not make a rate any less current.
+    pub fn set_rate(&mut self, from: Currency, to: Currency, rate: PairRate, entered_by: String) -> u64 {
+        let before = self.rates.set(from, to, rate);
+
+        self.rates.touch(from, to, Local::now().naive_local());
+        self.record(None, TransactionKind::RateChange { from, to, before, after: rate, entered_by: Some(entered_by) })
+    }
+
+    // Problems a new rate would cause: a move larger than the limit from the rate it replaces,
+    // or arbitrage loops through the pair
+    pub fn rate_warnings(&self, from: Currency, to: Currency, rate: PairRate) -> Vec<String> {
+        let mut warnings = Vec::new();
+        let mut candidate = self.rates.clone();
+
+        if let Some(before) = self.rates.mid(from, to) {
+            let change = rate.mid() / before - 1.0;
+
+            if change.abs() > self.max_rate_move {
+                warnings.push(format!("The {from}/{to} mid rate moves {:+.2}% from {}, more than the {}% limit.",
+                    change * 100.0, format_rate(before), self.max_rate_move * 100.0));
+            }
+        }
+
+        candidate.set(from, to, rate);
+        for arbitrage in candidate.arbitrage_loops() {
+            if arbitrage.path.contains(&from) && arbitrage.path.contains(&to) {
+                warnings.push(format!("Arbitrage: {}.", arbitrage.describe()));
+            }
+        }
+
+        warnings
+    }
+
+    // Capped even when the data file was edited by hand
+    pub fn approval_threshold(&self) -> f64 {
+        self.approval_threshold.min(MAX_APPROVAL_THRESHOLD)
+    }
+
+    // Threshold from above 0 up to the cap, as a share of the previous mid rate
+    pub fn set_approval_threshold(&mut self, admin: u64, threshold: f64) -> Result<(), BankError> {
+        self.require_administrator(admin)?;
+        if !(threshold > 0.0 && threshold <= MAX_APPROVAL_THRESHOLD) {
+            return Err(BankError::ThresholdOutOfRange);
+        }
+
+        self.approval_threshold = threshold;
+        Ok(())
+    }
+
+    // Whether a new rate moves the mid rate customers get between its currencies, recorded either way round,
+    // by more than the approval threshold from the current or the last approved rate. A new direct pair always does,
+    // as it replaces a rate through other currencies.
+    pub fn needs_approval(&self, from: Currency, to: Currency, rate: PairRate) -> bool {
+        let moves_too_far = |before: f64| (rate.mid() / before - 1.0).abs() > self.approval_threshold();
+
+        match self.rates.quote(from, to) {
+            Some(before) if before.path.len() > 2 => true,
+            Some(before) => moves_too_far(before.mid_rate) || self.approved_mid(from, to).is_some_and(moves_too_far),
+            None => false,
+        }
+    }
+
+    fn approved_mid(&self, from: Currency, to: Currency) -> Option<f64> {
+        let approved = |from: Currency, to: Currency| self.approved_rates.get(&from).and_then(|r| r.get(&to)).map(|rate| rate.mid());
+
+        approved(from, to).or_else(|| approved(to, from).map(|mid| 1.0 / mid))
+    }
+
+    // Records the rates that changed and marks the unchanged ones as confirmed, on behalf of an administrator.
+    // Changes beyond the approval threshold are held back, replacing any change of the same pair still waiting.
+    pub fn apply_rates(&mut self, rows: &[RateRow], entered_by: &str, requested_by: u64) -> Result<RateUpdate, BankError> {
+        self.require_administrator(requested_by)?;
+        let mut update = RateUpdate { changed: 0, pending: Vec::new(), rejected: Vec::new() };
+
+        for row in rows {
+            if self.rates.get(row.from, row.to).is_none() && self.rates.get(row.to, row.from).is_some() {
+                update.rejected.push(RowError {
+                    line: row.line,
+                    message: format!("Rates for {}/{} are recorded as {}/{}; enter the row that way.", row.from, row.to, row.to, row.from),
+                });
+            } else if self.rates.get(row.from, row.to) == Some(row.rate) {
+                self.rates.touch(row.from, row.to, Local::now().naive_local());
+            } else if self.needs_approval(row.from, row.to, row.rate) {
+                let id = self.next_pending_rate_id;
+
+                self.pending_rates.retain(|p| (p.from, p.to) != (row.from, row.to));
+                self.pending_rates.push(PendingRate {
+                    id, from: row.from, to: row.to, rate: row.rate, entered_by: entered_by.to_string(), requested_by, requested_at: self.now(),
+                });
+                self.next_pending_rate_id += 1;
+                update.pending.push(id);
+            } else {
+                let before = self.rates.get(row.from, row.to).unwrap_or(row.rate);
+
+                self.approved_rates.entry(row.from).or_default().entry(row.to).or_insert(before);
+                self.set_rate(row.from, row.to, row.rate, entered_by.to_string());
+                update.changed += 1;
+            }
+        }
+
+        Ok(update)
+    }
+
+    // Fetches rates from a provider and applies the valid ones
+    pub fn refresh_rates(&mut self, provider: &dyn RateProvider, registry: &CurrencyRegistry, requested_by: u64) -> Result<RateUpdate, BankError> {
+        self.require_administrator(requested_by)?;
+        let (rows, errors) = provider.fetch(registry, &self.rates).map_err(BankError::RateSource)?;
+        let mut update = self.apply_rates(&rows, &provider.name(), requested_by)?;
+
+        update.rejected.extend(errors);
+        update.rejected.sort_by_key(|e| e.line);
+        Ok(update)
+    }
+
+    // Rate changes waiting for approval, oldest first
+    pub fn pending_rates(&self) -> &[PendingRate] {
+        &self.pending_rates
+    }
+
+    // Records a held back rate change once an administrator other than the one who requested it approves it.
+    // Returns the ID of the rate change.
+    pub fn approve_rate(&mut self, id: u64, approver: u64) -> Result<u64, BankError> {
+        self.require_administrator(approver)?;
+        let index = self.pending_rates.iter().position(|p| p.id == id).ok_or(BankError::PendingRateNotFound(id))?;
+
+        if self.pending_rates[index].requested_by == approver {
+            return Err(BankError::SecondApproverRequired);
+        }
+
+        let pending = self.pending_rates.remove(index);
+        let entered_by = format!("{}, approved by {}", pending.entered_by, self.accounts[&approver].name);
+
+        self.approved_rates.entry(pending.from).or_default().insert(pending.to, pending.rate);
+        Ok(self.set_rate(pending.from, pending.to, pending.rate, entered_by))
+    }
+
+    // Any administrator, including the one who requested it, may withdraw a held back rate change
+    pub fn reject_rate(&mut self, id: u64, admin: u64) -> Result<PendingRate, BankError> {
+        self.require_administrator(admin)?;
+        let index = self.pending_rates.iter().position(|p| p.id == id).ok_or(BankError::PendingRateNotFound(id))?;
+
+        Ok(self.pending_rates.remove(index))
+    }
+
+    // Refuses a conversion that relies on a rate older than the staleness limit on the wall clock. Rates stamped
+    // in the future were stamped with the business date by older versions, so they are not trusted either.
+    fn check_rate_age(&self, conversion: &Conversion) -> Result<(), BankError> {
+        let Some(hours) = self.max_rate_age_hours else {
+            return Ok(());
+        };
+        let now = Local::now().naive_local();
+        let oldest = now - TimeDelta::hours(hours as i64);
+
+        for pair in conversion.path.windows(2) {
+            if self.rates.as_of(pair[0], pair[1]).is_none_or(|as_of| as_of < oldest || as_of > now) {
+                return Err(BankError::StaleRate { from: pair[0], to: pair[1], hours });
+            }
+        }
+
+        Ok(())
+    }
+
+    // Rate table as it stood at a past moment. Transaction times take the business date, which stays put while the
+    // wall clock passes midnight, so everything recorded from the first transaction after the moment on is undone.
+    pub fn rates_at(&self, moment: NaiveDateTime) -> RateTable {
+        let first_after = self.transactions.iter().find(|t| t.timestamp > moment).map_or(u64::MAX, |t| t.id);
+
+        self.undo_rate_changes(|t| t.id >= first_after)
+    }
+
+    // Rate table in force when a transaction was made
+    pub fn rates_for(&self, id: u64) -> RateTable {
+        self.undo_rate_changes(|t| t.id > id)
+    }
+
+    // Rebuilds an earlier rate table by undoing the matching rate changes, newest first.
+    // Rates never changed since the bank was set up are taken to have always applied.
+    fn undo_rate_changes(&self, undo: impl Fn(&Transaction) -> bool) -> RateTable {
+        let mut rates = self.rates.clone();
+
+        for transaction in self.transactions.iter().rev().filter(|t| undo(t)) {
+            if let TransactionKind::RateChange { from, to, before, .. } = transaction.kind {
+                match before {
+                    Some(rate) => rates.set(from, to, rate),
+                    None => rates.remove(from, to),
+                };
+            }
+        }
+
+        rates
+    }
+
+    // Rate changes involving a currency, oldest first
+    pub fn rate_history(&self, currency: Currency) -> Vec<&Transaction> {
+        self.transactions.iter()
+            .filter(|t| matches!(t.kind, TransactionKind::RateChange { from, to, .. } if from == currency || to == currency))
+            .collect()
+    }
+
+    // Recorded rate changes that moved the mid rate by more than the limit, oldest first
+    pub fn large_rate_moves(&self) -> Vec<&Transaction> {
+        self.transactions.iter()
+            .filter(|t| matches!(t.kind, TransactionKind::RateChange { before: Some(before), after, .. }
+                if after.change_from(before).abs() > self.max_rate_move))
+            .collect()
+    }
+
+    // Transactions of one account within the date range, oldest first
+    pub fn statement(&self, number: u64, start: Option<NaiveDate>, end: Option<NaiveDate>) -> Vec<&Transaction> {
+        self.transactions.iter()
+            .filter(|t| t.account == Some(number) && t.is_within(start, end))
+            .collect()
+    }
+
+    // Gross interest, tax withheld and net interest posted to an account during a calendar year, per currency
+    pub fn tax_summary(&self, number: u64, year: i32) -> BTreeMap<Currency, (Money, Money, Money)> {
+        let mut summary: BTreeMap<Currency, (Money, Money, Money)> = BTreeMap::new();
+        let start = NaiveDate::from_ymd_opt(year, 1, 1);
+        let end = NaiveDate::from_ymd_opt(year, 12, 31);
+
+        for transaction in self.statement(number, start, end) {
+            if let Some((gross, tax)) = transaction.kind.withholding() {
+                summary.entry(gross.currency())
+                    .and_modify(|totals| {
+                        totals.0 += gross;
+                        totals.1 += tax;
+                        totals.2 += gross - tax;
+                    })
+                    .or_insert((gross, tax, gross - tax));
+            }
+        }
+
+        summary
+    }
+}
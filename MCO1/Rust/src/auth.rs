@@ -0,0 +1,131 @@
+// Account PINs, lockout after wrong PINs, roles, and the menu session of the account logged in.
+// PINs are never stored; only a salted hash of them is.
+
+use std::time::{Duration, Instant};
+
+use chrono::{NaiveDateTime, TimeDelta};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::error::BankError;
+
+pub const MIN_PIN_LENGTH: usize = 4;
+pub const MAX_PIN_LENGTH: usize = 6;
+// Wrong PINs in a row that lock the account
+pub const MAX_PIN_ATTEMPTS: u32 = 3;
+pub const LOCKOUT_MINUTES: i64 = 15;
+// The menu logs out when an answer is entered after this long without one
+pub const SESSION_TIMEOUT: Duration = Duration::from_secs(5 * 60);
+const SALT_BYTES: usize = 16;
+// Rehashing makes guessing a short PIN from a copied data file slower
+const HASH_ROUNDS: u32 = 10_000;
+
+// What an account logged in at the menu may do. Only administrators may change rates, limits, fees,
+// currencies and interest settings.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub enum Role {
+    #[default]
+    Teller,
+    Administrator,
+}
+
+impl Role {
+    pub const ALL: [Role; 2] = [Role::Teller, Role::Administrator];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Role::Teller => "Teller",
+            Role::Administrator => "Administrator",
+        }
+    }
+}
+
+// Salted hash of an account PIN and the wrong PINs entered since the last login
+#[derive(Serialize, Deserialize, Clone)]
+pub struct Credentials {
+    salt: String,
+    hash: String,
+    #[serde(default)]
+    failed_attempts: u32,
+    #[serde(default)]
+    locked_until: Option<NaiveDateTime>,
+}
+
+impl Credentials {
+    pub fn new(pin: &str) -> Result<Credentials, BankError> {
+        if !valid_pin(pin) {
+            return Err(BankError::InvalidPin);
+        }
+
+        let mut salt = [0u8; SALT_BYTES];
+        getrandom::getrandom(&mut salt).expect("ERROR: No random source for the PIN salt.");
+        let salt = to_hex(&salt);
+        let hash = hash_pin(&salt, pin);
+
+        Ok(Credentials { salt, hash, failed_attempts: 0, locked_until: None })
+    }
+
+    // Checks a PIN entered at the given time. Wrong PINs count toward a lockout; a right one clears the count.
+    pub fn verify(&mut self, pin: &str, now: NaiveDateTime) -> Result<(), BankError> {
+        if let Some(until) = self.locked_until {
+            if now < until {
+                return Err(BankError::AccountLocked { until });
+            }
+            self.locked_until = None;
+        }
+
+        if hash_pin(&self.salt, pin) == self.hash {
+            self.failed_attempts = 0;
+            return Ok(());
+        }
+
+        self.failed_attempts += 1;
+        if self.failed_attempts < MAX_PIN_ATTEMPTS {
+            return Err(BankError::WrongPin { attempts_left: MAX_PIN_ATTEMPTS - self.failed_attempts });
+        }
+
+        let until = now + TimeDelta::minutes(LOCKOUT_MINUTES);
+        self.failed_attempts = 0;
+        self.locked_until = Some(until);
+        Err(BankError::AccountLocked { until })
+    }
+}
+
+// PINs are 4 to 6 digits
+pub fn valid_pin(pin: &str) -> bool {
+    (MIN_PIN_LENGTH..=MAX_PIN_LENGTH).contains(&pin.len()) && pin.chars().all(|c| c.is_ascii_digit())
+}
+
+fn hash_pin(salt: &str, pin: &str) -> String {
+    let mut digest = Sha256::new().chain_update(salt).chain_update(pin).finalize();
+
+    for _ in 1..HASH_ROUNDS {
+        digest = Sha256::new().chain_update(salt).chain_update(digest).finalize();
+    }
+
+    to_hex(&digest)
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{b:02x}")).collect()
+}
+
+// Account logged in at the menu
+pub struct Session {
+    pub account: u64,
+    last_active: Instant,
+}
+
+impl Session {
+    pub fn new(account: u64, now: Instant) -> Session {
+        Session { account, last_active: now }
+    }
+
+    pub fn expired(&self, now: Instant) -> bool {
+        now.duration_since(self.last_active) >= SESSION_TIMEOUT
+    }
+
+    pub fn touch(&mut self, now: Instant) {
+        self.last_active = now;
+    }
+}
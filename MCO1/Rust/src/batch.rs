@@ -0,0 +1,203 @@
+// Replays a script of operations for training and QA runs without the menu. Each line is either a command as it
+// would be typed after MCO1, e.g. deposit --account 100001 --amount 500, or a JSON object such as
+// {"command": "deposit", "account": 100001, "amount": "500"}; positional words go in an "args" array.
+// Blank lines and lines starting with # are skipped.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::mem;
+
+use serde_json::{Value, json};
+
+use crate::account::Bank;
+use crate::cli::{self, Args, Failure};
+use crate::currency::CurrencyRegistry;
+use crate::storage::Storage;
+
+const PIN_MASK: &str = "****";
+// Options whose values are PINs
+const PIN_OPTIONS: [&str; 2] = ["pin", "new-pin"];
+
+// Runs every operation in the script against the bank and writes one JSON result per operation, followed by a
+// summary. Operations that succeed are kept even when a later one fails.
+pub fn run(bank: &mut Bank, registry: &CurrencyRegistry, storage: &Storage, args: &Args) -> Result<(), Failure> {
+    args.expect(1, 1, &["on-error", "log"])?;
+    let stop_on_error = match args.option("on-error") {
+        None | Some("stop") => true,
+        Some("continue") => false,
+        Some(other) => return Err(Failure::Usage(format!("--on-error must be stop or continue, not {other:?}."))),
+    };
+    let path = &args.words()[0];
+    let script = fs::read_to_string(path).map_err(|e| Failure::Data(format!("Could not read {path}: {e}")))?;
+    let mut results: Vec<Value> = Vec::new();
+    let mut first_failure: Option<Failure> = None;
+    let (mut succeeded, mut failed, mut skipped) = (0, 0, 0);
+    let mut changed = false;
+
+    for (i, line) in script.lines().enumerate() {
+        let (number, text) = (i + 1, line.trim());
+
+        if text.is_empty() || text.starts_with('#') {
+            continue;
+        }
+        let logged = masked(text);
+        if stop_on_error && first_failure.is_some() {
+            skipped += 1;
+            results.push(json!({ "line": number, "operation": logged, "status": "skipped" }));
+            continue;
+        }
+
+        match parse_line(text).and_then(|(command, args)| execute(&command, &args, bank, registry)) {
+            Ok(report) => {
+                succeeded += 1;
+                changed |= report.changed;
+                results.push(json!({ "line": number, "operation": logged, "status": "ok", "exit_code": 0, "output": report.lines }));
+            }
+            Err(failure) => {
+                failed += 1;
+                changed |= failure.changed_bank();
+                results.push(json!({
+                    "line": number, "operation": logged, "status": "failed", "exit_code": failure.exit_code(), "error": failure.message(),
+                }));
+                first_failure.get_or_insert(failure.at_line(number));
+            }
+        }
+    }
+    results.push(json!({ "summary": { "succeeded": succeeded, "failed": failed, "skipped": skipped } }));
+
+    if changed {
+        cli::save(bank, storage)?;
+    }
+
+    let log: String = results.iter().map(|result| format!("{result}\n")).collect();
+    match args.option("log") {
+        Some(log_path) => fs::write(log_path, log).map_err(|e| Failure::Data(format!("Could not write {log_path}: {e}")))?,
+        None => print!("{log}"),
+    }
+
+    first_failure.map_or(Ok(()), Err)
+}
+
+fn execute(command: &str, args: &Args, bank: &mut Bank, registry: &CurrencyRegistry) -> Result<cli::Report, Failure> {
+    if command == "batch" {
+        return Err(Failure::Usage("A script cannot run another script.".to_string()));
+    }
+
+    cli::dispatch(command, args, bank, registry)
+}
+
+// Operation as written to the log, with the value of any PIN hidden
+fn masked(text: &str) -> String {
+    if text.starts_with('{') {
+        return match serde_json::from_str::<Value>(text) {
+            Ok(Value::Object(mut fields)) if PIN_OPTIONS.iter().any(|name| fields.contains_key(*name)) => {
+                for name in PIN_OPTIONS {
+                    if let Some(pin) = fields.get_mut(name) {
+                        *pin = Value::from(PIN_MASK);
+                    }
+                }
+                Value::Object(fields).to_string()
+            }
+            Ok(_) => text.to_string(),
+            // Not valid JSON, so everything from the first PIN option on is left out
+            Err(_) => match PIN_OPTIONS.iter().filter_map(|name| text.find(&format!("\"{name}\""))).min() {
+                Some(i) => format!("{}{PIN_MASK}", &text[..i]),
+                None => text.to_string(),
+            },
+        };
+    }
+
+    let mut after_pin = false;
+    text.split_inclusive(char::is_whitespace)
+        .map(|piece| {
+            let word = piece.trim_end();
+            if word.is_empty() {
+                return piece.to_string();
+            }
+            let logged = if after_pin { format!("{PIN_MASK}{}", &piece[word.len()..]) } else { piece.to_string() };
+            after_pin = word.strip_prefix("--").is_some_and(|name| PIN_OPTIONS.contains(&name));
+            logged
+        })
+        .collect()
+}
+
+// Splits a line into the command and its arguments
+fn parse_line(text: &str) -> Result<(String, Args), Failure> {
+    if text.starts_with('{') {
+        return parse_json(text);
+    }
+
+    let words = split_words(text)?;
+    let (command, rest) = words.split_first().ok_or(Failure::Usage("Operation is empty.".to_string()))?;
+
+    Ok((command.clone(), Args::parse(rest)?))
+}
+
+// Splits on whitespace, keeping text in double quotes together so names like "Juan Dela Cruz" stay one word
+fn split_words(text: &str) -> Result<Vec<String>, Failure> {
+    let mut words = Vec::new();
+    let mut word = String::new();
+    let mut is_quoted = false;
+    let mut has_word = false;
+
+    for c in text.chars() {
+        if c == '"' {
+            is_quoted = !is_quoted;
+            has_word = true;
+        } else if c.is_whitespace() && !is_quoted {
+            if has_word {
+                words.push(mem::take(&mut word));
+                has_word = false;
+            }
+        } else {
+            word.push(c);
+            has_word = true;
+        }
+    }
+
+    if is_quoted {
+        return Err(Failure::Usage("Closing quote is missing.".to_string()));
+    } else if has_word {
+        words.push(word);
+    }
+
+    Ok(words)
+}
+
+// Keys other than command and args become options; true turns on a flag such as force
+fn parse_json(text: &str) -> Result<(String, Args), Failure> {
+    let value: Value = serde_json::from_str(text).map_err(|e| Failure::Usage(format!("Operation is not valid JSON: {e}")))?;
+    let fields = value.as_object().ok_or(Failure::Usage("Operation must be a JSON object.".to_string()))?;
+    let command = fields.get("command").and_then(Value::as_str).ok_or(Failure::Usage("Operation needs a command.".to_string()))?;
+    let mut words = Vec::new();
+    let mut options = BTreeMap::new();
+
+    for (name, field) in fields {
+        match (name.as_str(), field) {
+            ("command", _) => {}
+            ("args", Value::Array(items)) => {
+                for item in items {
+                    words.push(json_text(item).ok_or(Failure::Usage("Items of args must be strings or numbers.".to_string()))?);
+                }
+            }
+            (_, Value::Bool(true)) => {
+                options.insert(name.clone(), String::new());
+            }
+            (_, Value::Bool(false)) => {}
+            _ => {
+                let text = json_text(field).ok_or(Failure::Usage(format!("{name} must be a string or a number.")))?;
+                options.insert(name.clone(), text);
+            }
+        }
+    }
+
+    Ok((command.to_string(), Args::new(words, options)))
+}
+
+fn json_text(value: &Value) -> Option<String> {
+    match value {
+        Value::String(text) => Some(text.clone()),
+        Value::Number(number) => Some(number.to_string()),
+        _ => None,
+    }
+}
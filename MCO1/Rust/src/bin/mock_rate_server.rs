@@ -0,0 +1,77 @@
+// Local stand-in for a treasury rate feed, for testing the HTTP rate provider.
+// Usage: mock_rate_server [port] [rates.json]
+// Serves GET /rates with the JSON rate sheet, rereading the file on every request so rates can be
+// changed while it runs. Without a file it serves the rates the bank starts with.
+
+use std::env;
+use std::fs;
+use std::io::{BufRead, BufReader, Write};
+use std::net::{TcpListener, TcpStream};
+
+const DEFAULT_PORT: u16 = 8099;
+const DEFAULT_RATES: &str = r#"[
+  {"from": "PHP", "to": "USD", "rate": 0.017},
+  {"from": "PHP", "to": "JPY", "rate": 2.6},
+  {"from": "PHP", "to": "GBP", "rate": 0.013},
+  {"from": "PHP", "to": "EUR", "rate": 0.015},
+  {"from": "PHP", "to": "CNY", "rate": 0.12}
+]"#;
+
+fn main() {
+    let args: Vec<String> = env::args().collect();
+    let port = match args.get(1).map(|p| p.parse::<u16>()) {
+        Some(Ok(port)) => port,
+        Some(Err(_)) => {
+            println!("ERROR: Port must be a number from 0 to 65535.");
+            return;
+        }
+        None => DEFAULT_PORT,
+    };
+    let rates_file = args.get(2).cloned();
+
+    let listener = match TcpListener::bind(("127.0.0.1", port)) {
+        Ok(listener) => listener,
+        Err(e) => {
+            println!("ERROR: Could not listen on port {port}: {e}");
+            return;
+        }
+    };
+    println!("Serving exchange rates at http://127.0.0.1:{port}/rates");
+
+    for stream in listener.incoming() {
+        match stream {
+            Ok(stream) => respond(stream, rates_file.as_deref()),
+            Err(e) => println!("ERROR: Connection failed: {e}"),
+        }
+    }
+}
+
+fn respond(mut stream: TcpStream, rates_file: Option<&str>) {
+    let mut reader = BufReader::new(&stream);
+    let mut request_line = String::new();
+    let mut header = String::new();
+
+    if reader.read_line(&mut request_line).is_err() {
+        return;
+    }
+    println!("{}", request_line.trim());
+
+    // Read the rest of the request so closing the connection does not reset it
+    while reader.read_line(&mut header).is_ok_and(|n| n > 0) && !header.trim().is_empty() {
+        header.clear();
+    }
+
+    let path = request_line.split_whitespace().nth(1).unwrap_or_default();
+    let (status, body) = if path != "/rates" {
+        ("404 Not Found", "Not found".to_string())
+    } else {
+        match rates_file.map(fs::read_to_string) {
+            Some(Ok(rates)) => ("200 OK", rates),
+            Some(Err(e)) => ("500 Internal Server Error", format!("Could not read rates file: {e}")),
+            None => ("200 OK", DEFAULT_RATES.to_string()),
+        }
+    };
+
+    let _ = write!(stream, "HTTP/1.1 {status}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+        body.len());
+}
@@ -0,0 +1,593 @@
+// Subcommands for driving the app from scripts instead of the numbered menu. They work on the same data
+// files as the menu and never ask questions; anything missing or wrong ends the command with an exit code.
+
+use std::collections::BTreeMap;
+use std::process::ExitCode;
+
+use serde::de::DeserializeOwned;
+
+use crate::account::{Bank, RateUpdate};
+use crate::auth::Role;
+use crate::batch;
+use crate::error::BankError;
+use crate::currency::{CURRENCY_FILE, Currency, CurrencyInfo, CurrencyRegistry};
+use crate::money::Money;
+use crate::provider::ManualProvider;
+use crate::rates::{PairRate, format_rate};
+use crate::storage::{DATA_FILE, Storage};
+
+// Exit codes besides 0 for success
+const EXIT_REFUSED: u8 = 1;
+const EXIT_USAGE: u8 = 2;
+const EXIT_DATA: u8 = 3;
+// Options that take no value
+const FLAGS: [&str; 1] = ["force"];
+
+const USAGE: &str = "Usage: MCO1 [COMMAND] [OPTIONS]
+Without a command the interactive menu is started.
+
+Commands:
+  register --name NAME --pin PIN
+  set-up-admin --name NAME --pin PIN
+  deposit --account NUMBER --amount AMOUNT [--currency CODE] [--pin PIN]
+  withdraw --account NUMBER --pin PIN --amount AMOUNT [--currency CODE]
+  exchange --account NUMBER --pin PIN --amount AMOUNT --to CODE [--from CODE]
+  transfer --account NUMBER --pin PIN --to-account NUMBER --amount AMOUNT [--currency CODE] [--to-currency CODE]
+  balance --account NUMBER --pin PIN
+  accounts --admin NUMBER --pin PIN
+  rates list
+  rates set CODE BUY_RATE [SELL_RATE] --admin NUMBER --pin PIN [--from CODE] [--force]
+  rates refresh --admin NUMBER --pin PIN
+  rates pending
+  rates approve ID --admin NUMBER --pin PIN
+  rates reject ID --admin NUMBER --pin PIN
+  role --account NUMBER --role teller|administrator --admin NUMBER --pin PIN
+  set-pin --account NUMBER --new-pin PIN --admin NUMBER --pin PIN
+  end-of-day --admin NUMBER --pin PIN [--days COUNT]
+  batch SCRIPT [--on-error stop|continue] [--log FILE]
+  help
+
+Currencies default to PHP. PINs are 4 to 6 digits; after 3 wrong PINs in a row the account is locked for 15 minutes.
+Deposits need no PIN, but only show the updated balance when given the account's PIN.
+Accounts saved before PINs log in once an administrator sets their PIN. The first two accounts registered become
+administrators; data saved before roles has none until two are opened with set-up-admin.
+Rates set with warnings about arbitrage or large moves are only recorded with --force.
+Only administrators list every account, run end-of-day processing and change rates and roles. A rate change moving the mid rate by more than the approval threshold
+from the current or last approved rate waits until a different administrator approves it, and a promotion to administrator
+until a different administrator asks for it too.
+
+Exit Codes:
+  0   Done
+  1   Rate not recorded because of warnings or how its pair is recorded, or no rate provider chosen
+  2   Command or options not valid
+  3   Data files could not be read or written
+  10  Account or pending rate change does not exist
+  11  Account name or PIN not valid
+  12  Not enough funds, or funds left in an account being closed
+  13  Balance would go over 1,000,000
+  14  Amount not valid or not greater than 0
+  15  Currency not registered or retired
+  16  Same account or currency on both sides
+  17  No exchange rate, a stale rate, or rates changed during the exchange
+  18  Exchange rate not valid
+  19  Rate provider could not be read
+  20  Wrong PIN, account locked, or no PIN set yet
+  21  Administrator required, or a second administrator must approve";
+
+// Why a command did not complete, which decides the exit code
+pub enum Failure {
+    Bank(BankError),
+    Refused(String),
+    Usage(String),
+    Data(String),
+    // Failure of one operation in a batch script
+    Script { line: usize, failure: Box<Failure> },
+}
+
+impl Failure {
+    pub fn exit_code(&self) -> u8 {
+        match self {
+            Failure::Bank(e) => e.exit_code(),
+            Failure::Refused(_) => EXIT_REFUSED,
+            Failure::Usage(_) => EXIT_USAGE,
+            Failure::Data(_) => EXIT_DATA,
+            Failure::Script { failure, .. } => failure.exit_code(),
+        }
+    }
+
+    pub fn message(&self) -> String {
+        match self {
+            Failure::Bank(e) => e.to_string(),
+            Failure::Refused(message) | Failure::Usage(message) | Failure::Data(message) => message.clone(),
+            Failure::Script { line, failure } => format!("Line {line}: {}", failure.message()),
+        }
+    }
+
+    // A wrong PIN fails the command but still counts toward locking the account
+    pub fn changed_bank(&self) -> bool {
+        matches!(self, Failure::Bank(e) if e.counts_pin_attempt())
+    }
+
+    // Names the script line the failure came from
+    pub fn at_line(self, line: usize) -> Failure {
+        Failure::Script { line, failure: Box::new(self) }
+    }
+}
+
+impl From<BankError> for Failure {
+    fn from(e: BankError) -> Failure {
+        Failure::Bank(e)
+    }
+}
+
+// Lines to print once the command has succeeded, and whether the bank needs saving
+pub struct Report {
+    pub lines: Vec<String>,
+    pub changed: bool,
+}
+
+// Positional words and --name value options given after the command
+pub struct Args {
+    words: Vec<String>,
+    options: BTreeMap<String, String>,
+}
+
+impl Args {
+    pub fn new(words: Vec<String>, options: BTreeMap<String, String>) -> Args {
+        Args { words, options }
+    }
+
+    pub fn parse(args: &[String]) -> Result<Args, Failure> {
+        let mut words = Vec::new();
+        let mut options = BTreeMap::new();
+        let mut args = args.iter();
+
+        while let Some(arg) = args.next() {
+            let Some(name) = arg.strip_prefix("--") else {
+                words.push(arg.clone());
+                continue;
+            };
+            let value = if FLAGS.contains(&name) {
+                String::new()
+            } else {
+                args.next().ok_or(Failure::Usage(format!("--{name} needs a value.")))?.clone()
+            };
+
+            if options.insert(name.to_string(), value).is_some() {
+                return Err(Failure::Usage(format!("--{name} is given more than once.")));
+            }
+        }
+
+        Ok(Args { words, options })
+    }
+
+    // Rejects words and options the command does not take
+    pub fn expect(&self, words: usize, max_words: usize, options: &[&str]) -> Result<(), Failure> {
+        if let Some(name) = self.options.keys().find(|name| !options.contains(&name.as_str())) {
+            return Err(Failure::Usage(format!("--{name} is not an option of this command.")));
+        } else if self.words.len() < words {
+            return Err(Failure::Usage("Missing arguments.".to_string()));
+        } else if self.words.len() > max_words {
+            return Err(Failure::Usage(format!("Unexpected argument {:?}.", self.words[max_words])));
+        }
+
+        Ok(())
+    }
+
+    pub fn words(&self) -> &[String] {
+        &self.words
+    }
+
+    pub fn option(&self, name: &str) -> Option<&str> {
+        self.options.get(name).map(|value| value.as_str())
+    }
+
+    pub fn required(&self, name: &str) -> Result<&str, Failure> {
+        self.option(name).ok_or(Failure::Usage(format!("--{name} is required.")))
+    }
+
+    pub fn flag(&self, name: &str) -> bool {
+        self.options.contains_key(name)
+    }
+
+    fn account(&self, name: &str) -> Result<u64, Failure> {
+        let value = self.required(name)?;
+
+        value.parse().map_err(|_| Failure::Usage(format!("--{name} must be an account number, not {value:?}.")))
+    }
+
+    // Currency named by an option, the base currency when it is left out
+    fn currency<'a>(&self, name: &str, registry: &'a CurrencyRegistry) -> Result<&'a CurrencyInfo, Failure> {
+        self.option(name).map_or(Ok(registry.base()), |code| find_currency(code, registry))
+    }
+}
+
+// Runs one command and returns the exit code for the process
+pub fn run(args: &[String]) -> ExitCode {
+    let result = Args::parse(&args[1..]).and_then(|rest| execute(&args[0], rest));
+
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(failure) => {
+            eprintln!("ERROR: {}", failure.message());
+            if let Failure::Usage(_) = failure {
+                eprintln!("Run MCO1 help to list the commands.");
+            }
+            ExitCode::from(failure.exit_code())
+        }
+    }
+}
+
+fn execute(command: &str, args: Args) -> Result<(), Failure> {
+    if command == "help" || command == "--help" {
+        args.expect(0, 0, &[])?;
+        println!("{USAGE}");
+        return Ok(());
+    }
+
+    let registry: CurrencyRegistry = load(&Storage::new(CURRENCY_FILE), CurrencyRegistry::new)?;
+    registry.validate().map_err(|e| Failure::Data(format!("Could not load {CURRENCY_FILE}: {e}")))?;
+    let storage = Storage::new(DATA_FILE);
+    let mut bank: Bank = load(&storage, Bank::new)?;
+    bank.check_currencies(&registry).map_err(|e| Failure::Data(format!("Could not load {DATA_FILE}: {e}")))?;
+
+    if command == "batch" {
+        return batch::run(&mut bank, &registry, &storage, &args);
+    }
+
+    let report = match dispatch(command, &args, &mut bank, &registry) {
+        Ok(report) => report,
+        Err(failure) => {
+            if failure.changed_bank() {
+                save(&bank, &storage)?;
+            }
+            return Err(failure);
+        }
+    };
+
+    if report.changed {
+        save(&bank, &storage)?;
+    }
+    for line in report.lines {
+        println!("{line}");
+    }
+
+    Ok(())
+}
+
+// Carries out one command on the bank without saving it
+pub fn dispatch(command: &str, args: &Args, bank: &mut Bank, registry: &CurrencyRegistry) -> Result<Report, Failure> {
+    match command {
+        "register" => register(bank, registry, args),
+        "deposit" => deposit(bank, registry, args),
+        "withdraw" => withdraw(bank, registry, args),
+        "exchange" => exchange(bank, registry, args),
+        "transfer" => transfer(bank, registry, args),
+        "balance" => balance(bank, registry, args),
+        "accounts" => accounts(bank, registry, args),
+        "rates" => rates(bank, registry, args),
+        "role" => role(bank, args),
+        "set-pin" => set_pin(bank, args),
+        "set-up-admin" => set_up_admin(bank, registry, args),
+        "end-of-day" => end_of_day(bank, registry, args),
+        _ => Err(Failure::Usage(format!("Unknown command {command:?}."))),
+    }
+}
+
+pub fn save(bank: &Bank, storage: &Storage) -> Result<(), Failure> {
+    storage.save(bank).map_err(|e| Failure::Data(format!("Could not save data to {}: {e}", storage.path().display())))
+}
+
+// Unlike the menu, a corrupt data file is never replaced; it is reported and left alone
+fn load<T: DeserializeOwned>(storage: &Storage, default: fn() -> T) -> Result<T, Failure> {
+    match storage.load() {
+        Ok(value) => Ok(value.unwrap_or_else(default)),
+        Err(e) => Err(Failure::Data(format!("Could not load {}: {e}", storage.path().display()))),
+    }
+}
+
+fn find_currency<'a>(code: &str, registry: &'a CurrencyRegistry) -> Result<&'a CurrencyInfo, Failure> {
+    Currency::new(&code.to_ascii_uppercase())
+        .and_then(|code| registry.get(code))
+        .ok_or(Failure::Bank(BankError::UnknownCurrency(code.to_string())))
+}
+
+// Refuses currencies that can no longer take new money
+fn active(currency: &CurrencyInfo) -> Result<&CurrencyInfo, Failure> {
+    if currency.retired {
+        return Err(Failure::Bank(BankError::RetiredCurrency(currency.code)));
+    }
+
+    Ok(currency)
+}
+
+fn parse_amount(args: &Args, currency: &CurrencyInfo) -> Result<Money, Failure> {
+    Ok(Money::parse(args.required("amount")?, currency).map_err(BankError::from)?)
+}
+
+// Debits need the PIN of the account the money comes from
+fn authenticate(bank: &mut Bank, args: &Args, number: u64) -> Result<(), Failure> {
+    Ok(bank.log_in(number, args.required("pin")?)?)
+}
+
+// Listing accounts, end-of-day processing, and rate and role changes need an administrator's account number and PIN
+fn administrator(bank: &mut Bank, args: &Args) -> Result<u64, Failure> {
+    let number = args.account("admin")?;
+
+    authenticate(bank, args, number)?;
+    bank.require_administrator(number)?;
+    Ok(number)
+}
+
+fn register(bank: &mut Bank, registry: &CurrencyRegistry, args: &Args) -> Result<Report, Failure> {
+    args.expect(0, 0, &["name", "pin"])?;
+    let name = args.required("name")?.trim();
+    let number = bank.open(name.to_string(), args.required("pin")?, registry.base())?;
+
+    Ok(Report { lines: vec![format!("Account Number: {number}")], changed: true })
+}
+
+// Anyone may pay into an account, but only its owner sees the balance afterwards
+fn deposit(bank: &mut Bank, registry: &CurrencyRegistry, args: &Args) -> Result<Report, Failure> {
+    args.expect(0, 0, &["account", "amount", "currency", "pin"])?;
+    let number = args.account("account")?;
+    let currency = active(args.currency("currency", registry)?)?;
+    let amount = parse_amount(args, currency)?;
+
+    let owner = args.option("pin").is_some();
+    if owner {
+        authenticate(bank, args, number)?;
+    }
+    let id = bank.deposit(number, amount, currency)?;
+    let result = if owner { updated_balance(bank, registry, number, currency) } else { format!("Deposited: {}", registry.format(amount)) };
+
+    Ok(Report { lines: vec![result, format!("Transaction ID: {id}")], changed: true })
+}
+
+fn withdraw(bank: &mut Bank, registry: &CurrencyRegistry, args: &Args) -> Result<Report, Failure> {
+    args.expect(0, 0, &["account", "pin", "amount", "currency"])?;
+    let number = args.account("account")?;
+    let currency = args.currency("currency", registry)?;
+    let amount = parse_amount(args, currency)?;
+
+    authenticate(bank, args, number)?;
+    let id = bank.withdraw(number, amount)?;
+
+    Ok(Report { lines: vec![updated_balance(bank, registry, number, currency), format!("Transaction ID: {id}")], changed: true })
+}
+
+// Exchanges at the current quote straight away, as there is nobody to confirm it
+fn exchange(bank: &mut Bank, registry: &CurrencyRegistry, args: &Args) -> Result<Report, Failure> {
+    args.expect(0, 0, &["account", "pin", "amount", "from", "to"])?;
+    let number = args.account("account")?;
+    let currency = args.currency("from", registry)?;
+    let to_currency = active(find_currency(args.required("to")?, registry)?)?;
+    let amount = parse_amount(args, currency)?;
+
+    authenticate(bank, args, number)?;
+    let quote = bank.quote_exchange(number, amount, to_currency)?;
+    let (debit_id, credit_id) = bank.exchange(&quote, to_currency)?;
+    let account = bank.get(number).unwrap();
+
+    Ok(Report {
+        lines: vec![
+            format!("Exchange Rate: 1 {} = {} {}", currency.code, format_rate(quote.conversion.rate), to_currency.code),
+            format!("Amount Debited: {}", registry.format(quote.amount)),
+            format!("Fee: {}", registry.format(quote.fee)),
+            format!("Amount Credited: {}", registry.format(quote.credit)),
+            format!("Updated Balances: {}, {}", registry.format(account.balance(currency)), registry.format(account.balance(to_currency))),
+            format!("Transaction IDs: {debit_id} (debit), {credit_id} (credit)"),
+        ],
+        changed: true,
+    })
+}
+
+fn transfer(bank: &mut Bank, registry: &CurrencyRegistry, args: &Args) -> Result<Report, Failure> {
+    args.expect(0, 0, &["account", "pin", "to-account", "amount", "currency", "to-currency"])?;
+    let number = args.account("account")?;
+    let to = args.account("to-account")?;
+    let currency = args.currency("currency", registry)?;
+    let to_currency = match args.option("to-currency") {
+        Some(_) => active(args.currency("to-currency", registry)?)?,
+        None => currency,
+    };
+    let amount = parse_amount(args, currency)?;
+
+    authenticate(bank, args, number)?;
+    let (debit_id, credit_id) = bank.transfer(number, to, amount, to_currency)?;
+    let credit = bank.transaction(credit_id).and_then(|t| t.kind.amounts()).map(|(credit, _, _)| credit).unwrap();
+
+    Ok(Report {
+        lines: vec![
+            format!("Amount Credited to {to}: {}", registry.format(credit)),
+            updated_balance(bank, registry, number, currency),
+            format!("Transaction IDs: {debit_id} (debit), {credit_id} (credit)"),
+        ],
+        changed: true,
+    })
+}
+
+fn updated_balance(bank: &Bank, registry: &CurrencyRegistry, number: u64, currency: &CurrencyInfo) -> String {
+    format!("Updated Balance: {}", registry.format(bank.get(number).unwrap().balance(currency)))
+}
+
+fn balance(bank: &mut Bank, registry: &CurrencyRegistry, args: &Args) -> Result<Report, Failure> {
+    args.expect(0, 0, &["account", "pin"])?;
+    let number = args.account("account")?;
+    authenticate(bank, args, number)?;
+    let account = bank.get(number).ok_or(BankError::AccountNotFound(number))?;
+    let mut lines = vec![format!("Account Number: {}", account.number), format!("Account Name: {}", account.name)];
+
+    for wallet in account.wallets.values() {
+        lines.push(format!("{}: {}", wallet.balance.currency(), registry.format(wallet.balance)));
+    }
+
+    Ok(Report { lines, changed: false })
+}
+
+fn accounts(bank: &mut Bank, registry: &CurrencyRegistry, args: &Args) -> Result<Report, Failure> {
+    args.expect(0, 0, &["admin", "pin"])?;
+    administrator(bank, args)?;
+    let mut lines = vec!["Account Number | Account Name | Balances |".to_string()];
+
+    for account in bank.accounts() {
+        let balances: Vec<String> = account.wallets.values().map(|w| registry.format(w.balance)).collect();
+        lines.push(format!("{} | {} | {} |", account.number, account.name, balances.join(", ")));
+    }
+
+    Ok(Report { lines, changed: false })
+}
+
+fn rates(bank: &mut Bank, registry: &CurrencyRegistry, args: &Args) -> Result<Report, Failure> {
+    match args.words.first().map(|word| word.as_str()) {
+        Some("list") => {
+            args.expect(1, 1, &[])?;
+            let lines = bank.rates.pairs()
+                .map(|(from, to, rate)| format!("{from}/{to} | {} | {} |", format_rate(rate.buy), format_rate(rate.sell)))
+                .collect();
+
+            Ok(Report { lines, changed: false })
+        }
+        Some("set") => set_rate(bank, registry, args),
+        Some("refresh") => {
+            args.expect(1, 1, &["admin", "pin"])?;
+            let admin = administrator(bank, args)?;
+            let provider = bank.rate_provider.provider()
+                .ok_or(Failure::Refused("Rates are entered manually. Choose a file or HTTP provider first.".to_string()))?;
+            let update = bank.refresh_rates(provider.as_ref(), registry, admin)?;
+            let mut lines = vec![format!("Rates refreshed from {}: {} changed.", provider.name(), update.changed)];
+
+            lines.extend(pending_lines(bank, &update));
+            for error in &update.rejected {
+                lines.push(format!("Rejected Row {}: {}", error.line, error.message));
+            }
+
+            Ok(Report { lines, changed: true })
+        }
+        Some("pending") => {
+            args.expect(1, 1, &[])?;
+            let lines = bank.pending_rates().iter()
+                .map(|p| format!("{} | {}/{} | {} | {} | {} ({}) |", p.id, p.from, p.to, format_rate(p.rate.buy), format_rate(p.rate.sell),
+                    p.entered_by, p.requested_by))
+                .collect();
+
+            Ok(Report { lines, changed: false })
+        }
+        Some(decision @ ("approve" | "reject")) => {
+            args.expect(2, 2, &["admin", "pin"])?;
+            let id = args.words[1].parse::<u64>()
+                .map_err(|_| Failure::Usage(format!("Pending rate change ID {:?} is not a number.", args.words[1])))?;
+            let admin = administrator(bank, args)?;
+            let line = if decision == "approve" {
+                format!("Exchange rate recorded. Transaction ID: {}", bank.approve_rate(id, admin)?)
+            } else {
+                bank.reject_rate(id, admin)?;
+                format!("Pending rate change {id} rejected.")
+            };
+
+            Ok(Report { lines: vec![line], changed: true })
+        }
+        _ => Err(Failure::Usage("Use rates list, set, refresh, pending, approve or reject.".to_string())),
+    }
+}
+
+// Notes the rate changes held back for a second administrator
+fn pending_lines(bank: &Bank, update: &RateUpdate) -> Vec<String> {
+    update.pending.iter()
+        .map(|id| format!("Pending rate change {id} moves the mid rate by more than {}%; a second administrator must approve it.",
+            format_rate(bank.approval_threshold() * 100.0)))
+        .collect()
+}
+
+// rates set USD 0.017 records 1 PHP = 0.017 USD; a second rate makes the first the buy rate and it the sell rate
+fn set_rate(bank: &mut Bank, registry: &CurrencyRegistry, args: &Args) -> Result<Report, Failure> {
+    args.expect(3, 4, &["from", "admin", "pin", "force"])?;
+    let from = active(args.currency("from", registry)?)?.code;
+    let to = active(find_currency(&args.words[1], registry)?)?.code;
+    let number = |word: &String| word.parse::<f64>().map_err(|_| Failure::Usage(format!("Rate {word:?} is not a number.")));
+    let buy = number(&args.words[2])?;
+    let sell = args.words.get(3).map_or(Ok(buy), number)?;
+    let rate = PairRate { buy, sell };
+
+    if from == to {
+        return Err(Failure::Usage("Foreign currency must differ from the base currency.".to_string()));
+    }
+    rate.validate()?;
+    let admin = administrator(bank, args)?;
+
+    let mut lines: Vec<String> = bank.rate_warnings(from, to, rate).iter().map(|w| format!("WARNING: {w}")).collect();
+    if !lines.is_empty() && !args.flag("force") {
+        return Err(Failure::Refused(format!("Rate not recorded. Pass --force to record it anyway.\n{}", lines.join("\n"))));
+    }
+
+    let provider = ManualProvider { entered_by: bank.get(admin).unwrap().name.clone(), rates: vec![(from, to, rate)] };
+    let update = bank.refresh_rates(&provider, registry, admin)?;
+    if let Some(error) = update.rejected.first() {
+        return Err(Failure::Refused(format!("Rate not recorded. {}", error.message)));
+    }
+    match update.changed {
+        _ if !update.pending.is_empty() => lines.extend(pending_lines(bank, &update)),
+        0 => lines.push("Rate unchanged; marked as current.".to_string()),
+        _ => lines.push("Exchange rate recorded.".to_string()),
+    }
+
+    Ok(Report { lines, changed: true })
+}
+
+fn role(bank: &mut Bank, args: &Args) -> Result<Report, Failure> {
+    args.expect(0, 0, &["account", "role", "admin", "pin"])?;
+    let number = args.account("account")?;
+    let role = match args.required("role")? {
+        "teller" => Role::Teller,
+        "administrator" => Role::Administrator,
+        other => return Err(Failure::Usage(format!("--role must be teller or administrator, not {other:?}."))),
+    };
+    let admin = administrator(bank, args)?;
+
+    let line = if bank.set_role(admin, number, role)? {
+        format!("Updated Role: {number} is now {}", role.name())
+    } else {
+        format!("Promotion of {number} waits for a second administrator to ask for it too.")
+    };
+
+    Ok(Report { lines: vec![line], changed: true })
+}
+
+fn set_pin(bank: &mut Bank, args: &Args) -> Result<Report, Failure> {
+    args.expect(0, 0, &["account", "new-pin", "admin", "pin"])?;
+    let number = args.account("account")?;
+    let pin = args.required("new-pin")?;
+    let admin = administrator(bank, args)?;
+
+    bank.set_pin(admin, number, pin)?;
+
+    Ok(Report { lines: vec![format!("PIN set for account {number}.")], changed: true })
+}
+
+fn set_up_admin(bank: &mut Bank, registry: &CurrencyRegistry, args: &Args) -> Result<Report, Failure> {
+    args.expect(0, 0, &["name", "pin"])?;
+    let name = args.required("name")?.trim();
+    let number = bank.set_up_administrator(name.to_string(), args.required("pin")?, registry.base())?;
+
+    Ok(Report { lines: vec![format!("Account Number: {number}"), format!("Role: {}", Role::Administrator.name())], changed: true })
+}
+
+fn end_of_day(bank: &mut Bank, registry: &CurrencyRegistry, args: &Args) -> Result<Report, Failure> {
+    args.expect(0, 0, &["days", "admin", "pin"])?;
+    let days = match args.option("days").map(|days| days.parse::<u32>()) {
+        None => 1,
+        Some(Ok(days)) if (1..=crate::menu::MAX_END_OF_DAY_RUN).contains(&days) => days,
+        Some(_) => return Err(Failure::Usage(format!("--days must be between 1 and {}.", crate::menu::MAX_END_OF_DAY_RUN))),
+    };
+    administrator(bank, args)?;
+    let mut postings = 0;
+
+    for _ in 0..days {
+        postings += bank.end_of_day(registry).len();
+    }
+
+    Ok(Report {
+        lines: vec![format!("Interest Postings: {postings}"), format!("Business Date: {}", bank.business_date())],
+        changed: true,
+    })
+}
@@ -0,0 +1,210 @@
+use core::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use crate::money::Money;
+
+// ISO 4217 alphabetic code such as "PHP"
+#[derive(Hash, Eq, PartialEq, Ord, PartialOrd, Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct Currency([u8; 3]);
+
+impl Currency {
+    // Home currency of the bank; new accounts open in it and it can never be retired
+    pub const PHP: Currency = Currency(*b"PHP");
+
+    pub fn new(code: &str) -> Option<Currency> {
+        match *code.as_bytes() {
+            [a, b, c] if code.bytes().all(|b| b.is_ascii_uppercase()) => Some(Currency([a, b, c])),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &str {
+        // Only ever built from ASCII letters
+        std::str::from_utf8(&self.0).unwrap()
+    }
+}
+
+impl TryFrom<String> for Currency {
+    type Error = String;
+
+    fn try_from(code: String) -> Result<Currency, String> {
+        Currency::new(&code).ok_or_else(|| format!("invalid currency code {code:?}"))
+    }
+}
+
+impl From<Currency> for String {
+    fn from(currency: Currency) -> String {
+        currency.as_str().to_string()
+    }
+}
+
+impl fmt::Display for Currency {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct CurrencyInfo {
+    pub code: Currency,
+    pub name: String,
+    pub symbol: String,
+    // Number of decimal places used when storing and rounding amounts
+    pub minor_units: u32,
+    #[serde(default)]
+    pub retired: bool,
+}
+
+impl CurrencyInfo {
+    fn new(code: Currency, name: &str, symbol: &str, minor_units: u32) -> CurrencyInfo {
+        CurrencyInfo { code, name: name.to_string(), symbol: symbol.to_string(), minor_units, retired: false }
+    }
+
+    // Amount with symbol and thousands separators, e.g. "₱1,234.56" or "¥1,235"
+    pub fn format(&self, amount: Money) -> String {
+        let plain = amount.to_string();
+        let (sign, digits) = match plain.strip_prefix('-') {
+            Some(digits) => ("-", digits),
+            None => ("", plain.as_str()),
+        };
+        let (whole, fraction) = match digits.split_once('.') {
+            Some((whole, fraction)) => (whole, format!(".{fraction}")),
+            None => (digits, String::new()),
+        };
+        let mut grouped = String::new();
+
+        for (i, c) in whole.chars().enumerate() {
+            if i > 0 && (whole.len() - i) % 3 == 0 {
+                grouped.push(',');
+            }
+            grouped.push(c);
+        }
+
+        format!("{sign}{}{grouped}{fraction}", self.symbol)
+    }
+}
+
+impl fmt::Display for CurrencyInfo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} ({})", self.name, self.code)
+    }
+}
+
+pub const CURRENCY_FILE: &str = "data/currencies.json";
+
+// Highest number of decimal places in ISO 4217 (e.g. the Chilean Unidad de Fomento)
+const MAX_MINOR_UNITS: u32 = 4;
+
+// Currencies the bank deals in; retired currencies stay listed so existing balances keep their details
+#[derive(Serialize, Deserialize)]
+pub struct CurrencyRegistry {
+    currencies: Vec<CurrencyInfo>,
+}
+
+impl Default for CurrencyRegistry {
+    fn default() -> CurrencyRegistry {
+        CurrencyRegistry::new()
+    }
+}
+
+impl CurrencyRegistry {
+    pub fn new() -> CurrencyRegistry {
+        CurrencyRegistry {
+            currencies: vec![
+                CurrencyInfo::new(Currency::PHP, "Philippine Peso", "₱", 2),
+                CurrencyInfo::new(Currency(*b"USD"), "United States Dollar", "$", 2),
+                CurrencyInfo::new(Currency(*b"JPY"), "Japanese Yen", "¥", 0),
+                CurrencyInfo::new(Currency(*b"GBP"), "British Pound Sterling", "£", 2),
+                CurrencyInfo::new(Currency(*b"EUR"), "Euro", "€", 2),
+                CurrencyInfo::new(Currency(*b"CNY"), "Chinese Yuan Renminbi", "CN¥", 2),
+            ],
+        }
+    }
+
+    // Checks a registry loaded from file before it is used
+    pub fn validate(&self) -> Result<(), String> {
+        match self.get(Currency::PHP) {
+            None => return Err(format!("base currency {} is missing", Currency::PHP)),
+            Some(base) if base.retired => return Err(format!("base currency {} is retired", Currency::PHP)),
+            Some(_) => {}
+        }
+
+        for (i, currency) in self.currencies.iter().enumerate() {
+            if self.currencies[..i].iter().any(|c| c.code == currency.code) {
+                return Err(format!("currency {} is listed more than once", currency.code));
+            } else if currency.minor_units > MAX_MINOR_UNITS {
+                return Err(format!("currency {} has more than {MAX_MINOR_UNITS} minor units", currency.code));
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn get(&self, code: Currency) -> Option<&CurrencyInfo> {
+        self.currencies.iter().find(|c| c.code == code)
+    }
+
+    // Details of a currency already held somewhere in the bank
+    pub fn info(&self, code: Currency) -> &CurrencyInfo {
+        self.get(code).unwrap_or_else(|| panic!("currency {code} is missing from the registry"))
+    }
+
+    pub fn format(&self, amount: Money) -> String {
+        self.info(amount.currency()).format(amount)
+    }
+
+    pub fn base(&self) -> &CurrencyInfo {
+        self.info(Currency::PHP)
+    }
+
+    pub fn all(&self) -> &[CurrencyInfo] {
+        &self.currencies
+    }
+
+    pub fn active(&self) -> Vec<&CurrencyInfo> {
+        self.currencies.iter().filter(|c| !c.retired).collect()
+    }
+
+    // Adds a new currency, or reinstates a retired one with updated details
+    pub fn add(&mut self, code: &str, name: &str, symbol: &str, minor_units: u32) -> Result<(), String> {
+        let code = Currency::new(code).ok_or("Currency code must be 3 uppercase letters (ISO 4217).")?;
+
+        if name.trim().is_empty() || symbol.trim().is_empty() {
+            return Err("Currency name and symbol must not be empty.".to_string());
+        } else if minor_units > MAX_MINOR_UNITS {
+            return Err(format!("Minor units must be between 0 and {MAX_MINOR_UNITS}."));
+        }
+
+        match self.currencies.iter_mut().find(|c| c.code == code) {
+            Some(existing) if !existing.retired => Err(format!("{code} is already registered.")),
+            Some(existing) if existing.minor_units != minor_units => {
+                Err(format!("{code} was registered with {} minor units and must keep them.", existing.minor_units))
+            }
+            Some(existing) => {
+                *existing = CurrencyInfo::new(code, name.trim(), symbol.trim(), minor_units);
+                Ok(())
+            }
+            None => {
+                self.currencies.push(CurrencyInfo::new(code, name.trim(), symbol.trim(), minor_units));
+                Ok(())
+            }
+        }
+    }
+
+    pub fn retire(&mut self, code: Currency) -> Result<(), String> {
+        if code == Currency::PHP {
+            return Err(format!("{code} is the base currency and cannot be retired."));
+        }
+
+        match self.currencies.iter_mut().find(|c| c.code == code) {
+            Some(existing) if existing.retired => Err(format!("{code} is already retired.")),
+            Some(existing) => {
+                existing.retired = true;
+                Ok(())
+            }
+            None => Err(format!("{code} is not registered.")),
+        }
+    }
+}
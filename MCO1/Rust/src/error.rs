@@ -0,0 +1,140 @@
+use core::fmt;
+
+use chrono::NaiveDateTime;
+
+use crate::currency::Currency;
+use crate::money::ParseMoneyError;
+
+// Operation an amount is checked for, named in error messages
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Action {
+    Deposit,
+    Withdrawal,
+    Transfer,
+    Exchange,
+}
+
+impl fmt::Display for Action {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Action::Deposit => write!(f, "deposit"),
+            Action::Withdrawal => write!(f, "withdrawal"),
+            Action::Transfer => write!(f, "transfer"),
+            Action::Exchange => write!(f, "exchange"),
+        }
+    }
+}
+
+// Why the bank refused an operation
+#[derive(Debug, PartialEq)]
+pub enum BankError {
+    NotRegistered,
+    NotLoggedIn,
+    AccountNotFound(u64),
+    InvalidName,
+    InvalidPin,
+    WrongPin { attempts_left: u32 },
+    AccountLocked { until: NaiveDateTime },
+    // Accounts opened before PINs were introduced need an administrator to set one before logging in
+    NoPin(u64),
+    AdministratorOnly,
+    // Setting up administrators is only for data saved before roles, until it has two
+    AdministratorExists,
+    // Demoting or closing one of the last two administrators would leave nobody to approve what the other enters
+    LastAdministrator,
+    // The administrator who entered a rate change or asked for a promotion cannot also approve it
+    SecondApproverRequired,
+    PendingRateNotFound(u64),
+    // The account has nothing in any currency to withdraw, exchange or transfer
+    NoFunds,
+    // Closing an account that still holds money
+    HasFunds,
+    InsufficientFunds { currency: Currency, action: Action },
+    CapExceeded { account: u64, currency: Currency, action: Action },
+    InvalidAmount(ParseMoneyError),
+    NotPositive(Action),
+    AmountTooSmall(Currency),
+    UnknownCurrency(String),
+    RetiredCurrency(Currency),
+    SameAccount,
+    SameCurrency,
+    NoRate { from: Currency, to: Currency },
+    StaleRate { from: Currency, to: Currency, hours: u32 },
+    QuoteExpired,
+    RateOutOfRange,
+    BuyAboveSell,
+    ThresholdOutOfRange,
+    // The rate provider could not be reached or sent something unreadable
+    RateSource(String),
+}
+
+impl BankError {
+    // Wrong PINs change the lockout count, so the bank has to be saved even though the operation failed
+    pub fn counts_pin_attempt(&self) -> bool {
+        matches!(self, BankError::WrongPin { .. } | BankError::AccountLocked { .. })
+    }
+
+    // Exit code of a subcommand refused for this reason; 2 and 3 are kept for bad usage and data errors
+    pub fn exit_code(&self) -> u8 {
+        match self {
+            BankError::NotRegistered | BankError::NotLoggedIn | BankError::AccountNotFound(_) | BankError::PendingRateNotFound(_) => 10,
+            BankError::InvalidName | BankError::InvalidPin => 11,
+            BankError::NoFunds | BankError::HasFunds | BankError::InsufficientFunds { .. } => 12,
+            BankError::CapExceeded { .. } => 13,
+            BankError::InvalidAmount(_) | BankError::NotPositive(_) | BankError::AmountTooSmall(_) => 14,
+            BankError::UnknownCurrency(_) | BankError::RetiredCurrency(_) => 15,
+            BankError::SameAccount | BankError::SameCurrency => 16,
+            BankError::NoRate { .. } | BankError::StaleRate { .. } | BankError::QuoteExpired => 17,
+            BankError::RateOutOfRange | BankError::BuyAboveSell | BankError::ThresholdOutOfRange => 18,
+            BankError::RateSource(_) => 19,
+            BankError::WrongPin { .. } | BankError::AccountLocked { .. } | BankError::NoPin(_) => 20,
+            BankError::AdministratorOnly | BankError::AdministratorExists | BankError::LastAdministrator | BankError::SecondApproverRequired => 21,
+        }
+    }
+}
+
+impl fmt::Display for BankError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BankError::NotRegistered => write!(f, "Register first."),
+            BankError::NotLoggedIn => write!(f, "Log in to an account first."),
+            BankError::AccountNotFound(number) => write!(f, "Account {number} does not exist."),
+            BankError::InvalidName => write!(f, "Input not valid. Alphabets and spaces are allowed."),
+            BankError::InvalidPin => write!(f, "PIN must be 4 to 6 digits."),
+            BankError::WrongPin { attempts_left } => write!(f, "Wrong PIN. {attempts_left} attempt(s) left before the account is locked."),
+            BankError::AccountLocked { until } => write!(f, "Too many wrong PINs. The account is locked until {}.", until.format("%Y-%m-%d %H:%M")),
+            BankError::NoPin(number) => write!(f, "Account {number} has no PIN yet. Ask an administrator to set one."),
+            BankError::AdministratorOnly => write!(f, "Only administrators can do this."),
+            BankError::AdministratorExists => write!(f, "The bank already has its administrators set up."),
+            BankError::LastAdministrator => write!(f, "The bank must keep at least two administrators."),
+            BankError::SecondApproverRequired => write!(f, "A different administrator must approve this change."),
+            BankError::PendingRateNotFound(id) => write!(f, "Pending rate change {id} does not exist."),
+            BankError::NoFunds => write!(f, "The account has no funds. Deposit first."),
+            BankError::HasFunds => write!(f, "Withdraw the remaining balance in every currency before closing the account."),
+            BankError::InsufficientFunds { currency, action } => write!(f, "{currency} balance must be at least 0 after the {action}."),
+            BankError::CapExceeded { account, currency, action } =>
+                write!(f, "{currency} balance of account {account} must be less than or equal to 1,000,000 after the {action}."),
+            BankError::InvalidAmount(e) => write!(f, "{e}"),
+            BankError::NotPositive(action) => write!(f, "Amount of the {action} must be greater than 0."),
+            BankError::AmountTooSmall(currency) => write!(f, "Amount is too small to convert into {currency}."),
+            BankError::UnknownCurrency(code) => write!(f, "{code:?} is not a registered currency."),
+            BankError::RetiredCurrency(currency) => write!(f, "{currency} is retired."),
+            BankError::SameAccount => write!(f, "Cannot transfer to the same account."),
+            BankError::SameCurrency => write!(f, "Cannot exchange same currency."),
+            BankError::NoRate { from, to } => write!(f, "No exchange rate recorded from {from} to {to}."),
+            BankError::StaleRate { from, to, hours } =>
+                write!(f, "The {from}/{to} rate is more than {hours} hours old. Refresh exchange rates first."),
+            BankError::QuoteExpired => write!(f, "Quote expired because exchange rates were updated. Request a new quote."),
+            BankError::RateOutOfRange => write!(f, "Exchange rate must be greater than 0 and less than 100,000."),
+            BankError::BuyAboveSell => write!(f, "Buy rate must not be higher than the sell rate."),
+            BankError::ThresholdOutOfRange => write!(f, "Approval threshold must be above 0% and at most 10%."),
+            BankError::RateSource(message) => write!(f, "{message}"),
+        }
+    }
+}
+
+impl From<ParseMoneyError> for BankError {
+    fn from(e: ParseMoneyError) -> BankError {
+        BankError::InvalidAmount(e)
+    }
+}
@@ -0,0 +1,108 @@
+use std::collections::BTreeMap;
+
+use chrono::{NaiveDate, NaiveDateTime};
+use serde::{Deserialize, Serialize};
+
+use crate::currency::Currency;
+use crate::money::Money;
+
+// How the fee on an exchange is worked out, charged in the currency being exchanged
+#[derive(Serialize, Deserialize, Clone, Copy)]
+pub enum FeeRule {
+    Percentage(f64),
+    // Flat amount in the source currency, capped at the amount exchanged
+    Flat(Money),
+}
+
+impl FeeRule {
+    pub fn fee(self, amount: Money) -> Money {
+        match self {
+            FeeRule::Percentage(rate) => amount.multiply(rate),
+            FeeRule::Flat(fee) if fee > amount => amount,
+            FeeRule::Flat(fee) => fee,
+        }
+    }
+}
+
+// Exchange fees by source currency, falling back to a default percentage
+#[derive(Serialize, Deserialize)]
+pub struct FeeSchedule {
+    pub default_percentage: f64,
+    rules: BTreeMap<Currency, FeeRule>,
+}
+
+impl Default for FeeSchedule {
+    fn default() -> FeeSchedule {
+        FeeSchedule::new()
+    }
+}
+
+impl FeeSchedule {
+    // Exchanges are free until a fee is configured
+    pub fn new() -> FeeSchedule {
+        FeeSchedule { default_percentage: 0.0, rules: BTreeMap::new() }
+    }
+
+    pub fn rule(&self, currency: Currency) -> FeeRule {
+        self.rules.get(&currency).copied().unwrap_or(FeeRule::Percentage(self.default_percentage))
+    }
+
+    pub fn rules(&self) -> impl Iterator<Item = (Currency, FeeRule)> + '_ {
+        self.rules.iter().map(|(currency, rule)| (*currency, *rule))
+    }
+
+    pub fn set(&mut self, currency: Currency, rule: FeeRule) {
+        self.rules.insert(currency, rule);
+    }
+
+    // Returns whether the currency had its own rule
+    pub fn remove(&mut self, currency: Currency) -> bool {
+        self.rules.remove(&currency).is_some()
+    }
+
+    pub fn fee(&self, amount: Money) -> Money {
+        self.rule(amount.currency()).fee(amount)
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum IncomeKind {
+    ExchangeFee,
+    // Difference between converting at the mid rate and at the rate the customer got
+    Spread,
+}
+
+impl IncomeKind {
+    pub fn name(self) -> &'static str {
+        match self {
+            IncomeKind::ExchangeFee => "Exchange Fee",
+            IncomeKind::Spread => "Spread",
+        }
+    }
+}
+
+// Revenue earned by the bank on one transaction
+#[derive(Serialize, Deserialize)]
+pub struct IncomeEntry {
+    pub timestamp: NaiveDateTime,
+    pub transaction: u64,
+    pub kind: IncomeKind,
+    pub amount: Money,
+}
+
+// Income within the date range totalled by kind and currency
+pub fn income_report(ledger: &[IncomeEntry], start: Option<NaiveDate>, end: Option<NaiveDate>) -> BTreeMap<(IncomeKind, Currency), Money> {
+    let mut totals: BTreeMap<(IncomeKind, Currency), Money> = BTreeMap::new();
+
+    for entry in ledger {
+        let date = entry.timestamp.date();
+
+        if start.is_none_or(|start| date >= start) && end.is_none_or(|end| date <= end) {
+            totals.entry((entry.kind, entry.amount.currency()))
+                .and_modify(|total| *total += entry.amount)
+                .or_insert(entry.amount);
+        }
+    }
+
+    totals
+}
@@ -0,0 +1,158 @@
+use std::fs;
+use std::path::Path;
+
+use serde_json::Value;
+
+use crate::currency::{Currency, CurrencyRegistry};
+use crate::rates::{PairRate, RateTable};
+
+// One valid row of a rate sheet
+pub struct RateRow {
+    pub line: usize,
+    pub from: Currency,
+    pub to: Currency,
+    pub rate: PairRate,
+}
+
+// Why a row of a rate sheet was rejected
+pub struct RowError {
+    pub line: usize,
+    pub message: String,
+}
+
+// Fields of one row before they are checked
+struct RawRow {
+    line: usize,
+    from: String,
+    to: String,
+    buy: Option<f64>,
+    sell: Option<f64>,
+}
+
+// Valid rows of a rate sheet and the rows that were rejected
+pub type RateSheet = (Vec<RateRow>, Vec<RowError>);
+
+// Reads a rate sheet, choosing CSV or JSON by the file extension
+pub fn read_rate_sheet(path: &Path, registry: &CurrencyRegistry, rates: &RateTable) -> Result<RateSheet, String> {
+    let contents = fs::read_to_string(path).map_err(|e| format!("Could not read {}: {e}", path.display()))?;
+
+    match path.extension().and_then(|e| e.to_str()).map(|e| e.to_ascii_lowercase()).as_deref() {
+        Some("csv") => parse_rate_sheet(&contents, false, registry, rates),
+        Some("json") => parse_rate_sheet(&contents, true, registry, rates),
+        _ => Err("Rate sheet must be a .csv or .json file.".to_string()),
+    }
+}
+
+// Every row is checked, so one bad row does not stop the others from being imported
+pub fn parse_rate_sheet(contents: &str, is_json: bool, registry: &CurrencyRegistry, rates: &RateTable) -> Result<RateSheet, String> {
+    let mut errors: Vec<RowError> = Vec::new();
+    let raw_rows = if is_json { parse_json(contents, &mut errors)? } else { parse_csv(contents, &mut errors)? };
+    let mut rows: Vec<RateRow> = Vec::new();
+
+    for raw in raw_rows {
+        match check_row(&raw, registry, rates, &rows) {
+            Ok(row) => rows.push(row),
+            Err(message) => errors.push(RowError { line: raw.line, message }),
+        }
+    }
+    errors.sort_by_key(|e| e.line);
+
+    Ok((rows, errors))
+}
+
+// Expects a header naming the from and to columns and either a rate column or buy and sell columns
+fn parse_csv(contents: &str, errors: &mut Vec<RowError>) -> Result<Vec<RawRow>, String> {
+    let mut lines = contents.lines().enumerate().filter(|(_, l)| !l.trim().is_empty());
+    let header: Vec<String> = match lines.next() {
+        Some((_, header)) => header.split(',').map(|c| c.trim().to_ascii_lowercase()).collect(),
+        None => return Err("Rate sheet is empty.".to_string()),
+    };
+    let column = |name: &str| header.iter().position(|c| c == name);
+
+    let (from, to) = match (column("from"), column("to")) {
+        (Some(from), Some(to)) => (from, to),
+        _ => return Err("CSV header must name the from and to columns.".to_string()),
+    };
+    let (buy, sell) = match (column("buy"), column("sell"), column("rate")) {
+        (Some(buy), Some(sell), _) => (buy, sell),
+        (None, None, Some(rate)) => (rate, rate),
+        _ => return Err("CSV header must have a rate column or both buy and sell columns.".to_string()),
+    };
+
+    let mut rows = Vec::new();
+    for (i, line) in lines {
+        let fields: Vec<&str> = line.split(',').map(|f| f.trim()).collect();
+
+        if fields.len() != header.len() {
+            errors.push(RowError { line: i + 1, message: format!("Expected {} fields but found {}.", header.len(), fields.len()) });
+            continue;
+        }
+
+        rows.push(RawRow {
+            line: i + 1,
+            from: fields[from].to_string(),
+            to: fields[to].to_string(),
+            buy: fields[buy].parse().ok(),
+            sell: fields[sell].parse().ok(),
+        });
+    }
+
+    Ok(rows)
+}
+
+// Expects an array of objects with from and to and either rate or buy and sell; rows are numbered from 1
+fn parse_json(contents: &str, errors: &mut Vec<RowError>) -> Result<Vec<RawRow>, String> {
+    let values: Vec<Value> = serde_json::from_str(contents).map_err(|e| format!("Rate sheet is not a JSON array: {e}"))?;
+    let mut rows = Vec::new();
+
+    for (i, value) in values.iter().enumerate() {
+        let field = |name: &str| value.get(name);
+        let text = |name: &str| field(name).and_then(Value::as_str).unwrap_or_default().to_string();
+
+        if !value.is_object() {
+            errors.push(RowError { line: i + 1, message: "Row must be an object.".to_string() });
+            continue;
+        }
+
+        let (buy, sell) = match field("rate") {
+            Some(rate) => (rate.as_f64(), rate.as_f64()),
+            None => (field("buy").and_then(Value::as_f64), field("sell").and_then(Value::as_f64)),
+        };
+        rows.push(RawRow { line: i + 1, from: text("from"), to: text("to"), buy, sell });
+    }
+
+    Ok(rows)
+}
+
+// Checks a rate entered by hand the same way as a row of a rate sheet
+pub fn check_rate(line: usize, from: Currency, to: Currency, rate: PairRate, registry: &CurrencyRegistry, rates: &RateTable, accepted: &[RateRow]) -> Result<RateRow, String> {
+    let raw = RawRow { line, from: from.to_string(), to: to.to_string(), buy: Some(rate.buy), sell: Some(rate.sell) };
+
+    check_row(&raw, registry, rates, accepted)
+}
+
+fn check_row(raw: &RawRow, registry: &CurrencyRegistry, rates: &RateTable, accepted: &[RateRow]) -> Result<RateRow, String> {
+    let currency = |code: &str| match Currency::new(code).and_then(|c| registry.get(c)) {
+        Some(info) if !info.retired => Ok(info.code),
+        Some(_) => Err(format!("{code} is retired.")),
+        None => Err(format!("{code:?} is not a registered currency.")),
+    };
+    let from = currency(&raw.from)?;
+    let to = currency(&raw.to)?;
+
+    if from == to {
+        return Err("From and to currencies must differ.".to_string());
+    } else if let Some(first) = accepted.iter().find(|r| (r.from, r.to) == (from, to) || (r.from, r.to) == (to, from)) {
+        return Err(format!("{from}/{to} already appears on row {}.", first.line));
+    } else if rates.get(to, from).is_some() {
+        return Err(format!("Rates for {from}/{to} are recorded as {to}/{from}; enter the row that way."));
+    }
+
+    let rate = match (raw.buy, raw.sell) {
+        (Some(buy), Some(sell)) => PairRate { buy, sell },
+        _ => return Err("Rates must be numbers.".to_string()),
+    };
+    rate.validate().map_err(|e| e.to_string())?;
+
+    Ok(RateRow { line: raw.line, from, to, rate })
+}
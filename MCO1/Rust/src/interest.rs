@@ -0,0 +1,204 @@
+use chrono::{Datelike, Days, Months, NaiveDate, Weekday};
+use serde::{Deserialize, Serialize};
+
+use crate::money::Money;
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Compounding {
+    Daily,
+    Monthly,
+    Quarterly,
+    Annually,
+}
+
+impl Compounding {
+    pub const ALL: [Compounding; 4] = [Compounding::Daily, Compounding::Monthly, Compounding::Quarterly, Compounding::Annually];
+
+    pub fn periods_per_year(self) -> u32 {
+        match self {
+            Compounding::Daily => 365,
+            Compounding::Monthly => 12,
+            Compounding::Quarterly => 4,
+            Compounding::Annually => 1,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Compounding::Daily => "Daily",
+            Compounding::Monthly => "Monthly",
+            Compounding::Quarterly => "Quarterly",
+            Compounding::Annually => "Annually",
+        }
+    }
+
+    // Singular name of one compounding period, used as the projection table's first column
+    pub fn period_name(self) -> &'static str {
+        match self {
+            Compounding::Daily => "Day",
+            Compounding::Monthly => "Month",
+            Compounding::Quarterly => "Quarter",
+            Compounding::Annually => "Year",
+        }
+    }
+
+    // Whether interest accrued up to and including this day is posted at the end of it
+    pub fn is_posting_day(self, date: NaiveDate) -> bool {
+        let next = date + Days::new(1);
+
+        match self {
+            Compounding::Daily => true,
+            Compounding::Monthly => next.day() == 1,
+            Compounding::Quarterly => next.day() == 1 && next.month() % 3 == 1,
+            Compounding::Annually => next.ordinal() == 1,
+        }
+    }
+
+    // End date of the nth period, counted from the start so month ends do not drift (Jan 31 -> Feb 29 -> Mar 31)
+    pub fn period_end(self, start: NaiveDate, period: u32) -> NaiveDate {
+        match self {
+            Compounding::Daily => start + Days::new(period as u64),
+            Compounding::Monthly => start + Months::new(period),
+            Compounding::Quarterly => start + Months::new(period * 3),
+            Compounding::Annually => start + Months::new(period * 12),
+        }
+    }
+}
+
+// How the days between two dates are turned into a fraction of a year
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum DayCount {
+    Actual365,
+    Actual360,
+    ActualActual,
+    Thirty360,
+}
+
+impl DayCount {
+    pub const ALL: [DayCount; 4] = [DayCount::Actual365, DayCount::Actual360, DayCount::ActualActual, DayCount::Thirty360];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            DayCount::Actual365 => "Actual/365",
+            DayCount::Actual360 => "Actual/360",
+            DayCount::ActualActual => "Actual/Actual",
+            DayCount::Thirty360 => "30/360",
+        }
+    }
+
+    pub fn year_fraction(self, start: NaiveDate, end: NaiveDate) -> f64 {
+        let days = (end - start).num_days() as f64;
+
+        match self {
+            DayCount::Actual365 => days / 365.0,
+            DayCount::Actual360 => days / 360.0,
+            // ISDA: days falling in each calendar year are divided by that year's length
+            DayCount::ActualActual => {
+                let mut fraction = 0.0;
+                let mut from = start;
+
+                while from < end {
+                    let next_year = NaiveDate::from_ymd_opt(from.year() + 1, 1, 1).unwrap();
+                    let to = next_year.min(end);
+
+                    fraction += (to - from).num_days() as f64 / days_in_year(from.year()) as f64;
+                    from = to;
+                }
+
+                fraction
+            }
+            // US bond basis: the 31st counts as the 30th, and the end only does so when the start is the 30th or 31st
+            DayCount::Thirty360 => {
+                let d1 = start.day().min(30) as i64;
+                let d2 = if end.day() == 31 && d1 == 30 { 30 } else { end.day() as i64 };
+                let months = (end.year() - start.year()) as i64 * 12 + end.month() as i64 - start.month() as i64;
+
+                (months * 30 + d2 - d1) as f64 / 360.0
+            }
+        }
+    }
+}
+
+// Weekends are not business days; interest for them is accrued at the end of the Friday before
+pub fn next_business_day(date: NaiveDate) -> NaiveDate {
+    let mut next = date + Days::new(1);
+
+    while matches!(next.weekday(), Weekday::Sat | Weekday::Sun) {
+        next = next + Days::new(1);
+    }
+
+    next
+}
+
+fn days_in_year(year: i32) -> i64 {
+    if NaiveDate::from_ymd_opt(year, 2, 29).is_some() { 366 } else { 365 }
+}
+
+fn default_day_count() -> DayCount {
+    DayCount::Actual365
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy)]
+pub struct InterestSettings {
+    pub annual_rate: f64,
+    pub compounding: Compounding,
+    #[serde(default = "default_day_count")]
+    pub day_count: DayCount,
+}
+
+impl Default for InterestSettings {
+    fn default() -> InterestSettings {
+        InterestSettings { annual_rate: 0.05, compounding: Compounding::Daily, day_count: default_day_count() }
+    }
+}
+
+// Final withholding tax on Philippine peso deposit interest
+pub const DEFAULT_WITHHOLDING_TAX_RATE: f64 = 0.20;
+
+// Tax withheld from gross interest and the net interest left to credit
+pub fn withhold(gross: Money, tax_rate: f64) -> (Money, Money) {
+    let tax = gross.multiply(tax_rate);
+
+    (tax, gross - tax)
+}
+
+pub struct ProjectionRow {
+    pub period: u32,
+    pub start: NaiveDate,
+    pub end: NaiveDate,
+    pub principal: Money,
+    pub gross: Money,
+    pub tax: Money,
+    pub net: Money,
+    pub balance: Money,
+}
+
+// Compounds the net interest at the end of each period from the start date, rounding each period's
+// interest and tax to the currency's minor units
+pub fn project(balance: Money, settings: InterestSettings, tax_rate: f64, start: NaiveDate, periods: u32) -> Vec<ProjectionRow> {
+    let mut principal = balance;
+    let mut period_start = start;
+    let mut rows = Vec::new();
+
+    for period in 1..=periods {
+        let period_end = settings.compounding.period_end(start, period);
+        let fraction = settings.day_count.year_fraction(period_start, period_end);
+        let gross = principal.multiply(settings.annual_rate * fraction);
+        let (tax, net) = withhold(gross, tax_rate);
+
+        rows.push(ProjectionRow {
+            period,
+            start: period_start,
+            end: period_end,
+            principal,
+            gross,
+            tax,
+            net,
+            balance: principal + net,
+        });
+        principal += net;
+        period_start = period_end;
+    }
+
+    rows
+}
@@ -0,0 +1,15 @@
+pub mod account;
+pub mod auth;
+pub mod batch;
+pub mod cli;
+pub mod currency;
+pub mod error;
+pub mod fees;
+pub mod import;
+pub mod interest;
+pub mod menu;
+pub mod money;
+pub mod provider;
+pub mod rates;
+pub mod storage;
+pub mod transaction;
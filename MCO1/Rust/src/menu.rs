@@ -0,0 +1,1887 @@
+// Numbered menu the app runs in a terminal. It reads answers from and writes to a Console, so whole sessions
+// can be replayed from a script and compared against a recorded transcript.
+
+use std::fs;
+use std::io::{BufRead, Write};
+use std::path::Path;
+use std::process::ExitCode;
+use std::time::Instant;
+
+use chrono::{Datelike, NaiveDate, NaiveDateTime};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+use crate::account::{self, Account, Bank, ExchangeQuote};
+use crate::auth::{self, Role, SESSION_TIMEOUT, Session};
+use crate::currency::{Currency, CurrencyInfo, CurrencyRegistry};
+use crate::error::BankError;
+use crate::fees::{self, FeeRule};
+use crate::import;
+use crate::interest::{self, Compounding, DayCount, InterestSettings};
+use crate::money::Money;
+use crate::provider::{ManualProvider, ProviderConfig};
+use crate::rates::{PairRate, format_rate};
+use crate::storage::{Storage, StorageError};
+use crate::transaction::{CSV_HEADER, Transaction, TransactionKind};
+
+// Writes a line to the console, like println!
+macro_rules! say {
+    ($io:expr, $($arg:tt)*) => {
+        $io.say(&format!($($arg)*))
+    };
+}
+
+const MAX_PROJECTION_YEARS: u32 = 5;
+// About one year of business days
+pub const MAX_END_OF_DAY_RUN: u32 = 260;
+const STATEMENT_PAGE_SIZE: usize = 10;
+const STATEMENT_DIR: &str = "statements";
+// Rates, the account list, limits, fees, currencies, interest settings, end-of-day processing, income, rate approvals,
+// roles and PINs
+const ADMINISTRATOR_CHOICES: [&str; 14] = ["5", "8", "11", "12", "14", "15", "17", "18", "20", "21", "22", "24", "25", "26"];
+
+// Where the menu reads answers from and writes prompts and results to; stdin and stdout in the terminal
+pub struct Console<R, W> {
+    reader: R,
+    writer: W,
+    // Writes each answer after its prompt so a recorded session reads like the terminal
+    pub echo: bool,
+    // Account logged in at the menu, logged out by the first answer entered after too long without one
+    session: Option<Session>,
+}
+
+// Returned through every prompt when the menu has to stop waiting for answers
+enum Interrupted {
+    // Input ran out, instead of asking the same question forever
+    InputEnded,
+    // The answer came after the session timed out, so whatever was being done is abandoned
+    SessionExpired,
+}
+
+impl<R: BufRead, W: Write> Console<R, W> {
+    pub fn new(reader: R, writer: W) -> Console<R, W> {
+        Console { reader, writer, echo: false, session: None }
+    }
+
+    pub fn into_writer(self) -> W {
+        self.writer
+    }
+
+    fn print(&mut self, text: &str) {
+        write!(self.writer, "{text}").expect("ERROR: Output failed.");
+    }
+
+    fn say(&mut self, line: &str) {
+        writeln!(self.writer, "{line}").expect("ERROR: Output failed.");
+    }
+
+    fn input(&mut self, prompt: &str) -> Result<String, Interrupted> {
+        let mut user_input = String::new();
+
+        self.print(&format!("{}: ", prompt));
+        self.writer.flush().expect("ERROR: Output failed.");
+        if self.reader.read_line(&mut user_input).expect("ERROR: Input failed.") == 0 {
+            return Err(Interrupted::InputEnded);
+        }
+        if self.echo {
+            self.say(user_input.trim_end());
+        }
+        if let Some(session) = self.session.as_mut() {
+            let now = Instant::now();
+
+            if session.expired(now) {
+                self.session = None;
+                return Err(Interrupted::SessionExpired);
+            }
+            session.touch(now);
+        }
+
+        Ok(user_input.trim().to_string())
+    }
+
+    fn prompt(&mut self) -> Result<bool, Interrupted> {
+        self.confirm("\nBack to the Main Menu")
+    }
+
+    // Asks a yes or no question until Y or N is entered
+    fn confirm(&mut self, question: &str) -> Result<bool, Interrupted> {
+        let mut user_input: String;
+
+        loop {
+            user_input = self.input(&format!("{question} (Y/N)"))?;
+
+            if user_input == "Y" {
+                return Ok(true);
+            } else if user_input == "N" {
+                return Ok(false);
+            }
+
+            say!(self, "ERROR: Enter Y or N only.");
+        }
+    }
+}
+
+// Runs the menu until Exit is chosen or the input ends, keeping the bank and currencies in the given files
+pub fn run(io: &mut Console<impl BufRead, impl Write>, storage: &Storage, currency_storage: &Storage) -> ExitCode {
+    // Expired sessions are dealt with inside the session, so only the end of input gets here
+    session(io, storage, currency_storage).unwrap_or_else(|_| {
+        io.print("\nInput ended.");
+        ExitCode::SUCCESS
+    })
+}
+
+fn session(io: &mut Console<impl BufRead, impl Write>, storage: &Storage, currency_storage: &Storage) -> Result<ExitCode, Interrupted> {
+    // Init
+    say!(io, "Welcome to CSADPRG MCO1 Banking and Currency App made with Rust!");
+
+    let mut registry: CurrencyRegistry = match load(io, currency_storage, CurrencyRegistry::new)? {
+        Some(registry) => registry,
+        None => return Ok(ExitCode::FAILURE),
+    };
+    if let Err(e) = registry.validate() {
+        say!(io, "ERROR: Could not load {}: {}", currency_storage.path().display(), e);
+        return Ok(ExitCode::FAILURE);
+    }
+    // Write the registry out on first run so administrators have a file to edit
+    save(io, &registry, currency_storage);
+
+    let mut bank: Bank = match load(io, storage, Bank::new)? {
+        Some(bank) => bank,
+        None => return Ok(ExitCode::FAILURE),
+    };
+    if let Err(e) = bank.check_currencies(&registry) {
+        say!(io, "ERROR: Could not load {}: {}", storage.path().display(), e);
+        return Ok(ExitCode::FAILURE);
+    }
+    if bank.setting_up() && bank.accounts().any(|a| a.role != Role::Administrator) {
+        set_up_administrators(io, &mut bank, &registry, storage)?;
+    }
+
+    // Main loop
+    loop {
+        let choice = io.input(&format!("\nMain Menu (Business Date: {})
+[0] Exit
+[1] Register Account Name
+[2] Deposit Amount
+[3] Withdraw Amount
+[4] Currency Exchange
+[5] Record Exchange Rates
+[6] Show Interest Amount
+[7] Log In
+[8] List Accounts
+[9] Close Account
+[10] View Statement
+[11] Manage Currencies
+[12] Interest Settings
+[13] Year-End Tax Summary
+[14] Withholding Tax Rate
+[15] End of Day Processing
+[16] Transfer Funds
+[17] Exchange Fees
+[18] Income Report
+[19] Exchange Rate History
+[20] Import Exchange Rates
+[21] Rate Provider Settings
+[22] Check Exchange Rates
+[23] Log Out
+[24] Approve Rate Changes
+[25] User Roles
+[26] Set Account PIN
+Select Transaction", bank.business_date()));
+
+        let result = match choice {
+            Ok(choice) if choice == "0" => break,
+            Ok(choice) => choose(io, &choice, &mut bank, &mut registry, storage, currency_storage),
+            Err(e) => Err(e),
+        };
+
+        // An answer entered after the session timed out abandons whatever was being done
+        match result {
+            Err(Interrupted::SessionExpired) => say!(io, "Logged out after {} minutes without activity. Log in again.", SESSION_TIMEOUT.as_secs() / 60),
+            result => result?,
+        }
+    }
+
+    io.print("\nThank you and goodbye!");
+    Ok(ExitCode::SUCCESS)
+}
+
+// Runs a main menu choice other than Exit
+fn choose(io: &mut Console<impl BufRead, impl Write>, choice: &str, bank: &mut Bank, registry: &mut CurrencyRegistry,
+    storage: &Storage, currency_storage: &Storage) -> Result<(), Interrupted> {
+    if choice == "1" {
+        io.session = Some(Session::new(register(io, bank, registry, storage)?, Instant::now()));
+    } else if choice == "23" {
+        match io.session.take() {
+            Some(ended) => say!(io, "Logged Out: {}", ended.account),
+            None => say!(io, "ERROR: {}", BankError::NotLoggedIn),
+        }
+    } else if ADMINISTRATOR_CHOICES.contains(&choice) {
+        match io.session.as_ref().map(|s| (s.account, bank.require_administrator(s.account))) {
+            None if bank.is_empty() => say!(io, "ERROR: {}", BankError::NotRegistered),
+            None => say!(io, "ERROR: {}", BankError::NotLoggedIn),
+            Some((_, Err(e))) => say!(io, "ERROR: {e}"),
+            Some((admin, Ok(()))) => administer(io, choice, admin, bank, registry, storage, currency_storage)?,
+        }
+    } else if bank.is_empty() {
+        say!(io, "ERROR: {}", BankError::NotRegistered);
+    } else if choice == "7" {
+        log_in(io, bank, storage)?;
+    } else if choice == "9" {
+        close_account(io, bank, storage)?;
+    } else if let Some(number) = io.session.as_ref().map(|s| s.account) {
+        // Withdrawing, exchanging, projecting interest and transferring all need money in some wallet
+        if ["3", "4", "6", "16"].contains(&choice) && !bank.get(number).unwrap().has_funds() {
+            say!(io, "ERROR: {}", BankError::NoFunds);
+        } else if choice == "2" {
+            deposit(io, bank, registry, storage, number)?;
+        } else if choice == "3" {
+            withdraw(io, bank, registry, storage, number)?;
+        } else if choice == "4" {
+            exchange_currency(io, bank, registry, storage, number)?;
+        } else if choice == "6" {
+            show_interest(io, bank, registry, number)?;
+        } else if choice == "19" {
+            rate_history(io, bank, registry)?;
+        } else if choice == "10" {
+            view_statement(io, bank, registry, number)?;
+        } else if choice == "13" {
+            tax_summary(io, bank, registry, number)?;
+        } else if choice == "16" {
+            transfer(io, bank, registry, storage, number)?;
+        } else {
+            say!(io, "ERROR: Input not valid.");
+        }
+    } else {
+        say!(io, "ERROR: {}", BankError::NotLoggedIn);
+    }
+
+    Ok(())
+}
+
+// Runs a menu choice only administrators may make
+fn administer(io: &mut Console<impl BufRead, impl Write>, choice: &str, admin: u64, bank: &mut Bank, registry: &mut CurrencyRegistry,
+    storage: &Storage, currency_storage: &Storage) -> Result<(), Interrupted> {
+    match choice {
+        "5" => update_exchange_rate(io, bank, registry, storage, admin),
+        "8" => {
+            list_accounts(io, bank, registry, admin);
+            Ok(())
+        }
+        "11" => manage_currencies(io, registry, currency_storage),
+        "12" => interest_settings(io, bank, storage),
+        "14" => update_withholding_tax(io, bank, storage),
+        "15" => end_of_day(io, bank, registry, storage),
+        "17" => exchange_fees(io, bank, registry, storage),
+        "18" => income_report(io, bank, registry),
+        "20" => import_rates(io, bank, registry, storage, admin),
+        "21" => rate_provider_settings(io, bank, registry, storage, admin),
+        "22" => check_rates(io, bank, storage, admin),
+        "24" => approve_rates(io, bank, storage, admin),
+        "25" => user_roles(io, bank, storage, admin),
+        "26" => set_account_pin(io, bank, storage, admin),
+        _ => unreachable!("{choice} is not an administrator choice"),
+    }
+}
+
+// Loads saved data, offering to start over when the data file is corrupt
+fn load<T: DeserializeOwned>(io: &mut Console<impl BufRead, impl Write>, storage: &Storage, default: fn() -> T) -> Result<Option<T>, Interrupted> {
+    match storage.load() {
+        Ok(Some(value)) => return Ok(Some(value)),
+        Ok(None) => return Ok(Some(default())),
+        Err(StorageError::Io(e)) => {
+            say!(io, "ERROR: Could not read {}: {}", storage.path().display(), e);
+            return Ok(None);
+        }
+        Err(e) => say!(io, "ERROR: Could not load {}: {}", storage.path().display(), e),
+    }
+
+    if !io.confirm("\nBack up the corrupt data file and start with empty data")? {
+        say!(io, "Fix or remove {} and restart the app.", storage.path().display());
+        return Ok(None);
+    }
+
+    match storage.back_up_corrupt() {
+        Ok(backup_path) => {
+            say!(io, "Corrupt data file moved to {}", backup_path.display());
+            Ok(Some(default()))
+        }
+        Err(e) => {
+            say!(io, "ERROR: Could not back up {}: {}", storage.path().display(), e);
+            Ok(None)
+        }
+    }
+}
+
+fn save<T: Serialize>(io: &mut Console<impl BufRead, impl Write>, value: &T, storage: &Storage) {
+    if let Err(e) = storage.save(value) {
+        say!(io, "ERROR: Could not save data to {}: {}", storage.path().display(), e);
+    }
+}
+
+fn register(io: &mut Console<impl BufRead, impl Write>, bank: &mut Bank, registry: &CurrencyRegistry, storage: &Storage) -> Result<u64, Interrupted> {
+    let mut user_input: String;
+    let mut number: u64;
+
+    loop {
+        user_input = io.input("\nRegister Account Name
+Account Name")?;
+
+        if !account::valid_name(&user_input) {
+            say!(io, "ERROR: {}", BankError::InvalidName);
+            continue;
+        }
+        let pin = input_new_pin(io)?;
+
+        number = match bank.open(user_input, &pin, registry.base()) {
+            Ok(number) => number,
+            Err(e) => {
+                say!(io, "ERROR: {e}");
+                continue;
+            }
+        };
+        save(io, bank, storage);
+        say!(io, "Account Number: {number}");
+        say!(io, "Role: {}", bank.get(number).unwrap().role.name());
+
+        if io.prompt()? {
+            return Ok(number);
+        }
+    }
+}
+
+// Data saved before roles has no administrators, so whoever starts the app with it opens two before anything else
+fn set_up_administrators(io: &mut Console<impl BufRead, impl Write>, bank: &mut Bank, registry: &CurrencyRegistry, storage: &Storage) -> Result<(), Interrupted> {
+    say!(io, "\nNo administrators are set up for the saved accounts. Register the two administrator accounts first.");
+
+    while bank.setting_up() {
+        let name = io.input("Account Name")?;
+
+        if !account::valid_name(&name) {
+            say!(io, "ERROR: {}", BankError::InvalidName);
+            continue;
+        }
+        let pin = input_new_pin(io)?;
+
+        match bank.set_up_administrator(name, &pin, registry.base()) {
+            Ok(number) => {
+                save(io, bank, storage);
+                say!(io, "Account Number: {number}");
+                say!(io, "Role: {}", Role::Administrator.name());
+            }
+            Err(e) => say!(io, "ERROR: {e}"),
+        }
+    }
+
+    Ok(())
+}
+
+// Asks for a new PIN and for it again until a valid one is entered the same both times
+fn input_new_pin(io: &mut Console<impl BufRead, impl Write>) -> Result<String, Interrupted> {
+    loop {
+        let pin = io.input("PIN (4 to 6 digits)")?;
+
+        if !auth::valid_pin(&pin) {
+            say!(io, "ERROR: {}", BankError::InvalidPin);
+        } else if io.input("Confirm PIN")? == pin {
+            return Ok(pin);
+        } else {
+            say!(io, "ERROR: PINs do not match.");
+        }
+    }
+}
+
+// Accounts opened before PINs were introduced cannot log in until an administrator sets their PIN
+fn log_in(io: &mut Console<impl BufRead, impl Write>, bank: &mut Bank, storage: &Storage) -> Result<(), Interrupted> {
+    let mut user_input: String;
+    let mut number: u64;
+
+    loop {
+        user_input = io.input("\nLog In
+Account Number")?;
+
+        if user_input.parse::<u64>().is_err() {
+            say!(io, "ERROR: Input not valid.");
+            continue;
+        }
+
+        number = user_input.parse::<u64>().unwrap();
+
+        let result = match bank.get(number) {
+            None => Err(BankError::AccountNotFound(number)),
+            Some(account) if account.pin.is_none() => Err(BankError::NoPin(number)),
+            Some(_) => {
+                let pin = io.input("PIN")?;
+                let result = bank.log_in(number, &pin);
+
+                save(io, bank, storage);
+                result
+            }
+        };
+
+        match result {
+            Ok(()) => {
+                io.session = Some(Session::new(number, Instant::now()));
+                say!(io, "Logged In: {} ({})", number, bank.get(number).unwrap().name);
+            }
+            Err(e) => say!(io, "ERROR: {e}"),
+        }
+
+        if io.prompt()? {
+            break;
+        }
+    }
+
+    Ok(())
+}
+
+// Marks the account logged in
+fn list_accounts(io: &mut Console<impl BufRead, impl Write>, bank: &Bank, registry: &CurrencyRegistry, selected: u64) {
+    say!(io, "\nList Accounts
+Account Number | Account Name | Balances |");
+    for account in bank.accounts() {
+        let balances: Vec<String> = account.wallets.values().map(|w| registry.format(w.balance)).collect();
+
+        say!(io, "{}{} | {} | {} |",
+            if account.number == selected { "*" } else { "" },
+            account.number, account.name, balances.join(", "));
+    }
+}
+
+fn close_account(io: &mut Console<impl BufRead, impl Write>, bank: &mut Bank, storage: &Storage) -> Result<(), Interrupted> {
+    let user_input: String = io.input("\nClose Account
+Account Number")?;
+
+    let number = match user_input.parse::<u64>() {
+        Ok(number) => number,
+        Err(_) => {
+            say!(io, "ERROR: Input not valid.");
+            return Ok(());
+        }
+    };
+
+    // Accounts without a PIN yet need an administrator to set one before they can be closed
+    let result = match bank.get(number) {
+        None => Err(BankError::AccountNotFound(number)),
+        Some(account) if account.pin.is_none() => Err(BankError::NoPin(number)),
+        Some(_) => {
+            let pin = io.input("PIN")?;
+            let result = bank.log_in(number, &pin);
+
+            save(io, bank, storage);
+            result
+        }
+    };
+    if let Err(e) = result {
+        say!(io, "ERROR: {e}");
+        return Ok(());
+    }
+
+    if let Err(e) = bank.close(number) {
+        say!(io, "ERROR: {e}");
+        return Ok(());
+    }
+    save(io, bank, storage);
+    if io.session.as_ref().is_some_and(|s| s.account == number) {
+        io.session = None;
+    }
+    say!(io, "Closed Account: {number}");
+
+    Ok(())
+}
+
+fn deposit(io: &mut Console<impl BufRead, impl Write>, bank: &mut Bank, registry: &CurrencyRegistry, storage: &Storage, number: u64) -> Result<(), Interrupted> {
+    let mut user_input: String;
+    let mut currency: &CurrencyInfo;
+    let mut amount: Money;
+    let mut account: &Account;
+    let currencies = registry.active();
+
+    loop {
+        account = bank.get(number).unwrap();
+        say!(io, "\nDeposit Amount
+Account Number: {}
+Account Name: {}", account.number, account.name);
+
+        currency = match select_wallet(io, account, &currencies)? {
+            Some(currency) => currency,
+            None => {
+                say!(io, "ERROR: Input not valid.");
+                continue;
+            }
+        };
+        say!(io, "Current Balance: {}
+Currency: {}", registry.format(account.balance(currency)), currency);
+        user_input = io.input("Deposit Amount")?;
+
+        amount = match Money::parse(&user_input, currency) {
+            Ok(amount) => amount,
+            Err(e) => {
+                say!(io, "ERROR: {}", e);
+                continue;
+            }
+        };
+
+        match bank.deposit(number, amount, currency) {
+            Ok(id) => {
+                say!(io, "Updated Balance: {}", registry.format(bank.get(number).unwrap().balance(currency)));
+                say!(io, "Transaction ID: {id}");
+            }
+            Err(e) => {
+                say!(io, "ERROR: {e}");
+                continue;
+            }
+        }
+        save(io, bank, storage);
+
+        if io.prompt()? {
+            break;
+        }
+    }
+
+    Ok(())
+}
+
+fn withdraw(io: &mut Console<impl BufRead, impl Write>, bank: &mut Bank, registry: &CurrencyRegistry, storage: &Storage, number: u64) -> Result<(), Interrupted> {
+    let mut user_input: String;
+    let mut currency: &CurrencyInfo;
+    let mut amount: Money;
+    let mut account: &Account;
+
+    loop {
+        account = bank.get(number).unwrap();
+        say!(io, "\nWithdraw Amount
+Account Number: {}
+Account Name: {}", account.number, account.name);
+
+        currency = match select_wallet(io, account, &funded_wallets(account, registry))? {
+            Some(currency) => currency,
+            None => {
+                say!(io, "ERROR: Input not valid.");
+                continue;
+            }
+        };
+        say!(io, "Current Balance: {}
+Currency: {}", registry.format(account.balance(currency)), currency);
+        user_input = io.input("Withdraw Amount")?;
+
+        amount = match Money::parse(&user_input, currency) {
+            Ok(amount) => amount,
+            Err(e) => {
+                say!(io, "ERROR: {}", e);
+                continue;
+            }
+        };
+
+        match bank.withdraw(number, amount) {
+            Ok(id) => {
+                say!(io, "Updated Balance: {}", registry.format(bank.get(number).unwrap().balance(currency)));
+                say!(io, "Transaction ID: {id}");
+            }
+            Err(e) => {
+                say!(io, "ERROR: {e}");
+                continue;
+            }
+        }
+        save(io, bank, storage);
+
+        if !bank.get(number).unwrap().has_funds() || io.prompt()? {
+            break;
+        }
+    }
+
+    Ok(())
+}
+
+// Currencies the account holds a positive balance in
+fn funded_wallets<'a>(account: &Account, registry: &'a CurrencyRegistry) -> Vec<&'a CurrencyInfo> {
+    account.wallets.values()
+        .filter(|w| w.balance.is_positive())
+        .map(|w| registry.info(w.balance.currency()))
+        .collect()
+}
+
+// Lists the currencies with the account's balance in each and returns the one picked
+fn select_wallet<'a>(io: &mut Console<impl BufRead, impl Write>, account: &Account, currencies: &[&'a CurrencyInfo]) -> Result<Option<&'a CurrencyInfo>, Interrupted> {
+    let options: String = currencies.iter().enumerate()
+        .map(|(i, c)| match account.wallet(c.code) {
+            Some(wallet) => format!("[{i}] {c}: {}\n", c.format(wallet.balance)),
+            None => format!("[{i}] {c}: No wallet yet\n"),
+        })
+        .collect();
+    let user_input = io.input(&format!("\nWallet Options:
+{options}Select Wallet"))?;
+
+    Ok(select_currency(currencies, &user_input))
+}
+
+fn transfer(io: &mut Console<impl BufRead, impl Write>, bank: &mut Bank, registry: &CurrencyRegistry, storage: &Storage, number: u64) -> Result<(), Interrupted> {
+    let mut user_input: String;
+    let mut currency: &CurrencyInfo;
+    let mut to: u64;
+    let mut to_currency: &CurrencyInfo;
+    let mut amount: Money;
+    let mut account: &Account;
+    let currencies = registry.active();
+
+    loop {
+        account = bank.get(number).unwrap();
+        say!(io, "\nTransfer Funds
+Account Number: {}
+Account Name: {}", account.number, account.name);
+
+        currency = match select_wallet(io, account, &funded_wallets(account, registry))? {
+            Some(currency) => currency,
+            None => {
+                say!(io, "ERROR: Input not valid.");
+                continue;
+            }
+        };
+        say!(io, "Current Balance: {}
+Currency: {}", registry.format(account.balance(currency)), currency);
+        user_input = io.input("Destination Account Number")?;
+
+        to = match user_input.parse() {
+            Ok(to) if bank.get(to).is_some() => to,
+            Ok(to) => {
+                say!(io, "ERROR: {}", BankError::AccountNotFound(to));
+                continue;
+            }
+            Err(_) => {
+                say!(io, "ERROR: Input not valid.");
+                continue;
+            }
+        };
+        user_input = io.input(&format!("\nCredit Currency Options:
+{}Credit Currency", currency_options(&currencies)))?;
+
+        to_currency = match select_currency(&currencies, &user_input) {
+            Some(c) => c,
+            None => {
+                say!(io, "ERROR: Input not valid.");
+                continue;
+            }
+        };
+        user_input = io.input("Transfer Amount")?;
+
+        amount = match Money::parse(&user_input, currency) {
+            Ok(amount) => amount,
+            Err(e) => {
+                say!(io, "ERROR: {}", e);
+                continue;
+            }
+        };
+
+        let (debit_id, credit_id) = match bank.transfer(number, to, amount, to_currency) {
+            Ok(ids) => ids,
+            // Rules the amount alone cannot fix, such as a missing or stale rate, so offer a way out
+            Err(e) => {
+                say!(io, "ERROR: {}", e);
+                if io.prompt()? {
+                    break;
+                }
+                continue;
+            }
+        };
+
+        if let Some(TransactionKind::TransferIn { amount: credit, rate, .. }) = bank.transaction(credit_id).map(|t| &t.kind) {
+            if credit.currency() != amount.currency() {
+                say!(io, "Exchange Rate: 1 {} = {} {}", amount.currency(), format_rate(*rate), credit.currency());
+            }
+            say!(io, "Amount Credited to {}: {}", to, registry.format(*credit));
+        }
+        say!(io, "Updated Balance: {}", registry.format(bank.get(number).unwrap().balance(currency)));
+        say!(io, "Transaction IDs: {debit_id} (debit), {credit_id} (credit)");
+        save(io, bank, storage);
+
+        if !bank.get(number).unwrap().has_funds() || io.prompt()? {
+            break;
+        }
+    }
+
+    Ok(())
+}
+
+fn exchange_currency(io: &mut Console<impl BufRead, impl Write>, bank: &mut Bank, registry: &CurrencyRegistry, storage: &Storage, number: u64) -> Result<(), Interrupted> {
+    let mut user_input: String;
+    let mut currency: &CurrencyInfo;
+    let mut to_currency: &CurrencyInfo;
+    let mut quote: ExchangeQuote;
+    let mut amount: Money;
+    let mut account: &Account;
+    let currencies = registry.active();
+
+    loop {
+        account = bank.get(number).unwrap();
+        say!(io, "\nForeign Currency Exchange
+Account Number: {}
+Account Name: {}", account.number, account.name);
+
+        currency = match select_wallet(io, account, &funded_wallets(account, registry))? {
+            Some(currency) => currency,
+            None => {
+                say!(io, "ERROR: Input not valid.");
+                continue;
+            }
+        };
+        say!(io, "Source Currency: {}
+Source Balance: {}", currency, registry.format(account.balance(currency)));
+        user_input = io.input(&format!("\nExchange Currency Options:
+{}Exchange Currency", currency_options(&currencies)))?;
+
+        match select_currency(&currencies, &user_input) {
+            Some(c) if c.code == currency.code => {
+                say!(io, "ERROR: {}", BankError::SameCurrency);
+                continue;
+            }
+            Some(c) => to_currency = c,
+            None => {
+                say!(io, "ERROR: Input not valid.");
+                continue;
+            }
+        }
+
+        user_input = io.input("Amount to Exchange")?;
+
+        amount = match Money::parse(&user_input, currency) {
+            Ok(amount) => amount,
+            Err(e) => {
+                say!(io, "ERROR: {}", e);
+                continue;
+            }
+        };
+
+        quote = match bank.quote_exchange(number, amount, to_currency) {
+            Ok(quote) => quote,
+            Err(e) => {
+                say!(io, "ERROR: {}", e);
+                if io.prompt()? {
+                    break;
+                }
+                continue;
+            }
+        };
+
+        say!(io, "\nExchange Quote
+Exchange Rate: 1 {} = {} {}
+Conversion Path: {}
+Mid Rate: 1 {} = {} {}
+Amount Debited: {}
+Fee: {}
+Spread: {}
+Amount Credited: {}", currency.code, format_rate(quote.conversion.rate), to_currency.code, quote.conversion.describe_path(),
+            currency.code, format_rate(quote.conversion.mid_rate), to_currency.code,
+            registry.format(quote.amount), registry.format(quote.fee), registry.format(quote.spread), registry.format(quote.credit));
+
+        if !io.confirm("Confirm Exchange")? {
+            say!(io, "Exchange cancelled.");
+            if io.prompt()? {
+                break;
+            }
+            continue;
+        }
+
+        let (debit_id, credit_id) = match bank.exchange(&quote, to_currency) {
+            Ok(ids) => ids,
+            Err(e) => {
+                say!(io, "ERROR: {}", e);
+                if io.prompt()? {
+                    break;
+                }
+                continue;
+            }
+        };
+
+        account = bank.get(number).unwrap();
+        say!(io, "Updated Balances: {}, {}", registry.format(account.balance(currency)), registry.format(account.balance(to_currency)));
+        say!(io, "Transaction IDs: {debit_id} (debit), {credit_id} (credit)");
+        save(io, bank, storage);
+
+        if !account.has_funds() || io.prompt()? {
+            break;
+        }
+    }
+
+    Ok(())
+}
+
+fn update_exchange_rate(io: &mut Console<impl BufRead, impl Write>, bank: &mut Bank, registry: &CurrencyRegistry, storage: &Storage, admin: u64) -> Result<(), Interrupted> {
+    let mut user_input: String;
+    let mut from_currency: Option<Currency> = None;
+    let mut to_currency: Option<Currency> = None;
+    let mut rate: PairRate;
+    let currencies = registry.active();
+
+    loop {
+        if from_currency.is_none() {
+            say!(io, "\nRecord Exchange Rate
+Current Rates (Buy / Sell):");
+            for (from, to, rate) in bank.rates.pairs() {
+                say!(io, "1 {} = {} {}", from, describe_rate(rate), to);
+            }
+
+            user_input = io.input(&format!("\n{}Select Base Currency", currency_options(&currencies)))?;
+
+            match select_currency(&currencies, &user_input) {
+                Some(c) => from_currency = Some(c.code),
+                None => {
+                    say!(io, "ERROR: Input not valid.");
+                    continue;
+                }
+            }
+        }
+
+        if to_currency.is_none() {
+            user_input = io.input("Select Foreign Currency")?;
+
+            match select_currency(&currencies, &user_input) {
+                Some(c) if Some(c.code) == from_currency => {
+                    say!(io, "ERROR: Foreign currency must differ from the base currency.");
+                    continue;
+                }
+                Some(c) => to_currency = Some(c.code),
+                None => {
+                    say!(io, "ERROR: Input not valid.");
+                    continue;
+                }
+            }
+        }
+
+        let (from, to) = (from_currency.unwrap(), to_currency.unwrap());
+        let buy = io.input(&format!("Buy Rate (bank buys 1 {from} for ? {to})"))?.parse::<f64>();
+        let sell = io.input(&format!("Sell Rate (bank sells 1 {from} for ? {to})"))?.parse::<f64>();
+
+        rate = match (buy, sell) {
+            (Ok(buy), Ok(sell)) => PairRate { buy, sell },
+            _ => {
+                say!(io, "ERROR: Input not valid.");
+                continue;
+            }
+        };
+
+        if let Err(e) = rate.validate() {
+            say!(io, "ERROR: {e}");
+            continue;
+        }
+
+        let warnings = bank.rate_warnings(from, to, rate);
+        for warning in &warnings {
+            say!(io, "WARNING: {warning}");
+        }
+        if !warnings.is_empty() && !io.confirm("Record the rate anyway")? {
+            say!(io, "Rate not recorded.");
+            continue;
+        }
+
+        let provider = ManualProvider { entered_by: bank.get(admin).unwrap().name.clone(), rates: vec![(from, to, rate)] };
+        match bank.refresh_rates(&provider, registry, admin) {
+            Ok(update) if !update.rejected.is_empty() => say!(io, "ERROR: {}", update.rejected[0].message),
+            Ok(update) if !update.pending.is_empty() => say_pending(io, bank, &update.pending),
+            Ok(update) if update.changed == 0 => say!(io, "Rate unchanged; marked as current."),
+            Ok(_) => say!(io, "Exchange rate recorded."),
+            Err(e) => say!(io, "ERROR: {e}"),
+        }
+        save(io, bank, storage);
+
+        if io.prompt()? {
+            break;
+        }
+
+        from_currency = None;
+        to_currency = None;
+    }
+
+    Ok(())
+}
+
+// Previews a rate sheet and records its valid rows only once confirmed, so declining is a dry run
+fn import_rates(io: &mut Console<impl BufRead, impl Write>, bank: &mut Bank, registry: &CurrencyRegistry, storage: &Storage, admin: u64) -> Result<(), Interrupted> {
+    let path = io.input("\nImport Exchange Rates
+Rate Sheet File (.csv or .json)")?;
+
+    let (rows, errors) = match import::read_rate_sheet(Path::new(&path), registry, &bank.rates) {
+        Ok(result) => result,
+        Err(e) => {
+            say!(io, "ERROR: {e}");
+            return Ok(());
+        }
+    };
+    let changes: Vec<&import::RateRow> = rows.iter().filter(|r| bank.rates.get(r.from, r.to) != Some(r.rate)).collect();
+
+    say!(io, "\nPreview (Buy / Sell)
+Row | Pair | Current | New |");
+    for row in &rows {
+        let current = bank.rates.get(row.from, row.to);
+        say!(io, "{} | {}/{} | {} | {} |", row.line, row.from, row.to,
+            current.map_or("None".to_string(), describe_rate),
+            if current == Some(row.rate) { "Unchanged".to_string() } else { describe_rate(row.rate) });
+    }
+    if rows.is_empty() {
+        say!(io, "No valid rows.");
+    }
+
+    if !errors.is_empty() {
+        say!(io, "\nRejected Rows
+Row | Error |");
+        for error in &errors {
+            say!(io, "{} | {} |", error.line, error.message);
+        }
+    }
+
+    say!(io, "\n{} valid rows, {} to change, {} rejected.", rows.len(), changes.len(), errors.len());
+    if changes.is_empty() || !io.confirm(&format!("Record {} rate changes", changes.len()))? {
+        say!(io, "Dry run only; no rates were changed.");
+        return Ok(());
+    }
+
+    let entered_by = format!("{} (import from {path})", bank.get(admin).unwrap().name);
+    match bank.apply_rates(&rows, &entered_by, admin) {
+        Ok(update) => {
+            save(io, bank, storage);
+            say!(io, "Exchange rates imported: {} changed.", update.changed);
+            say_pending(io, bank, &update.pending);
+        }
+        Err(e) => say!(io, "ERROR: {e}"),
+    }
+
+    Ok(())
+}
+
+fn rate_provider_settings(io: &mut Console<impl BufRead, impl Write>, bank: &mut Bank, registry: &CurrencyRegistry, storage: &Storage, admin: u64) -> Result<(), Interrupted> {
+    let mut user_input: String;
+
+    loop {
+        say!(io, "\nRate Provider Settings
+Provider: {}
+Staleness Limit: {}", bank.rate_provider.describe(),
+            bank.max_rate_age_hours.map_or("None".to_string(), |hours| format!("{hours} hours")));
+        say!(io, "Pair | Last Recorded or Confirmed |");
+        for (from, to, _) in bank.rates.pairs() {
+            say!(io, "{}/{} | {} |", from, to,
+                bank.rates.as_of(from, to).map_or("Set up with the bank".to_string(), |at| at.format("%Y-%m-%d %H:%M:%S").to_string()));
+        }
+
+        user_input = io.input("\n[0] Back to the Main Menu
+[1] Use Manual Entry Only
+[2] Use Rate Sheet File
+[3] Use HTTP Endpoint
+[4] Set Staleness Limit
+[5] Refresh Rates Now
+Select Option")?;
+
+        if user_input == "0" {
+            break;
+        } else if user_input == "1" {
+            bank.rate_provider = ProviderConfig::Manual;
+        } else if user_input == "2" {
+            let path = io.input("Rate Sheet File (.csv or .json)")?;
+
+            if path.is_empty() {
+                say!(io, "ERROR: Input not valid.");
+                continue;
+            }
+            bank.rate_provider = ProviderConfig::File(path.into());
+        } else if user_input == "3" {
+            let url = io.input("Endpoint URL (e.g. http://127.0.0.1:8099/rates)")?;
+
+            if !url.starts_with("http://") {
+                say!(io, "ERROR: URL must start with http://");
+                continue;
+            }
+            bank.rate_provider = ProviderConfig::Http(url);
+        } else if user_input == "4" {
+            match io.input("Staleness Limit in Hours (0 for none)")?.parse::<u32>() {
+                Ok(0) => bank.max_rate_age_hours = None,
+                Ok(hours) => bank.max_rate_age_hours = Some(hours),
+                Err(_) => {
+                    say!(io, "ERROR: Input not valid.");
+                    continue;
+                }
+            }
+        } else if user_input == "5" {
+            let Some(provider) = bank.rate_provider.provider() else {
+                say!(io, "ERROR: Rates are entered manually. Choose a file or HTTP provider first.");
+                continue;
+            };
+
+            match bank.refresh_rates(provider.as_ref(), registry, admin) {
+                Ok(update) => {
+                    say!(io, "Rates refreshed from {}: {} changed.", provider.name(), update.changed);
+                    say_pending(io, bank, &update.pending);
+                    for error in &update.rejected {
+                        say!(io, "Rejected Row {}: {}", error.line, error.message);
+                    }
+                }
+                Err(e) => {
+                    say!(io, "ERROR: {e}");
+                    continue;
+                }
+            }
+        } else {
+            say!(io, "ERROR: Input not valid.");
+            continue;
+        }
+
+        save(io, bank, storage);
+    }
+
+    Ok(())
+}
+
+fn check_rates(io: &mut Console<impl BufRead, impl Write>, bank: &mut Bank, storage: &Storage, admin: u64) -> Result<(), Interrupted> {
+    let mut user_input: String;
+
+    loop {
+        let loops = bank.rates.arbitrage_loops();
+        let moves = bank.large_rate_moves();
+
+        say!(io, "\nCheck Exchange Rates
+Rate Move Limit: {}%
+Approval Threshold: {}%", bank.max_rate_move * 100.0, bank.approval_threshold() * 100.0);
+        say!(io, "Arbitrage Loops (at customer rates):");
+        for arbitrage in &loops {
+            say!(io, "{}", arbitrage.describe());
+        }
+        if loops.is_empty() {
+            say!(io, "None found.");
+        }
+
+        say!(io, "Rate Changes Beyond the Limit:");
+        say!(io, "ID | Effective | Pair | Before (Mid) | After (Mid) | Move |");
+        for transaction in &moves {
+            if let TransactionKind::RateChange { from, to, before: Some(before), after, .. } = &transaction.kind {
+                say!(io, "{} | {} | {}/{} | {} | {} | {:+.2}% |", transaction.id, transaction.timestamp.format("%Y-%m-%d %H:%M:%S"),
+                    from, to, format_rate(before.mid()), format_rate(after.mid()), after.change_from(*before) * 100.0);
+            }
+        }
+        if moves.is_empty() {
+            say!(io, "None found.");
+        }
+
+        user_input = io.input("\n[0] Back to the Main Menu
+[1] Set Rate Move Limit
+[2] Set Approval Threshold
+Select Option")?;
+
+        if user_input == "0" {
+            break;
+        } else if user_input == "1" {
+            match io.input("Rate Move Limit in Percent")?.parse::<f64>() {
+                Ok(percent) if percent > 0.0 && percent.is_finite() => bank.max_rate_move = percent / 100.0,
+                _ => {
+                    say!(io, "ERROR: Enter a percentage above 0.");
+                    continue;
+                }
+            }
+            save(io, bank, storage);
+        } else if user_input == "2" {
+            let result = match io.input("Approval Threshold in Percent")?.parse::<f64>() {
+                Ok(percent) => bank.set_approval_threshold(admin, percent / 100.0),
+                Err(_) => Err(BankError::ThresholdOutOfRange),
+            };
+            if let Err(e) = result {
+                say!(io, "ERROR: {e}");
+                continue;
+            }
+            save(io, bank, storage);
+        } else {
+            say!(io, "ERROR: Input not valid.");
+        }
+    }
+
+    Ok(())
+}
+
+// Notes rate changes held back until a second administrator approves them
+fn say_pending(io: &mut Console<impl BufRead, impl Write>, bank: &Bank, pending: &[u64]) {
+    for id in pending {
+        say!(io, "Pending Rate Change {id}: moves the mid rate by more than {}%. A second administrator must approve it.",
+            bank.approval_threshold() * 100.0);
+    }
+}
+
+fn approve_rates(io: &mut Console<impl BufRead, impl Write>, bank: &mut Bank, storage: &Storage, admin: u64) -> Result<(), Interrupted> {
+    let mut user_input: String;
+
+    loop {
+        say!(io, "\nApprove Rate Changes
+Approval Threshold: {}%
+ID | Pair | Current | Proposed | Move | Entered By |", bank.approval_threshold() * 100.0);
+        for pending in bank.pending_rates() {
+            let current = bank.rates.get(pending.from, pending.to);
+
+            say!(io, "{} | {}/{} | {} | {} | {} | {} |", pending.id, pending.from, pending.to,
+                current.map_or("None".to_string(), describe_rate), describe_rate(pending.rate),
+                current.map_or("New".to_string(), |before| format!("{:+.2}%", pending.rate.change_from(before) * 100.0)),
+                pending.entered_by);
+        }
+        if bank.pending_rates().is_empty() {
+            say!(io, "None waiting.");
+        }
+
+        user_input = io.input("\n[0] Back to the Main Menu
+[1] Approve
+[2] Reject
+Select Option")?;
+
+        if user_input == "0" {
+            break;
+        } else if user_input != "1" && user_input != "2" {
+            say!(io, "ERROR: Input not valid.");
+            continue;
+        }
+
+        let Ok(id) = io.input("Pending Rate Change ID")?.parse::<u64>() else {
+            say!(io, "ERROR: Input not valid.");
+            continue;
+        };
+        let result = if user_input == "1" {
+            bank.approve_rate(id, admin).map(|transaction| format!("Exchange rate recorded. Transaction ID: {transaction}"))
+        } else {
+            bank.reject_rate(id, admin).map(|_| format!("Pending rate change {id} rejected."))
+        };
+
+        match result {
+            Ok(message) => {
+                save(io, bank, storage);
+                say!(io, "{message}");
+            }
+            Err(e) => say!(io, "ERROR: {e}"),
+        }
+    }
+
+    Ok(())
+}
+
+fn user_roles(io: &mut Console<impl BufRead, impl Write>, bank: &mut Bank, storage: &Storage, admin: u64) -> Result<(), Interrupted> {
+    let mut user_input: String;
+
+    loop {
+        say!(io, "\nUser Roles
+Account Number | Account Name | Role |");
+        for account in bank.accounts() {
+            match bank.pending_promotion(account.number) {
+                Some(admin) => say!(io, "{} | {} | {} (promotion asked by {admin}) |", account.number, account.name, account.role.name()),
+                None => say!(io, "{} | {} | {} |", account.number, account.name, account.role.name()),
+            }
+        }
+
+        user_input = io.input("\nAccount Number")?;
+        let Ok(number) = user_input.parse::<u64>() else {
+            say!(io, "ERROR: Input not valid.");
+            continue;
+        };
+
+        user_input = io.input(&format!("\n{}Role", Role::ALL.iter().enumerate()
+            .map(|(i, role)| format!("[{i}] {}\n", role.name()))
+            .collect::<String>()))?;
+        let Some(role) = user_input.parse::<usize>().ok().and_then(|i| Role::ALL.get(i)) else {
+            say!(io, "ERROR: Input not valid.");
+            continue;
+        };
+
+        match bank.set_role(admin, number, *role) {
+            Ok(true) => {
+                save(io, bank, storage);
+                say!(io, "Updated Role: {number} is now {}", role.name());
+            }
+            Ok(false) => {
+                save(io, bank, storage);
+                say!(io, "Promotion of {number} waits for a second administrator to ask for it too.");
+            }
+            Err(e) => say!(io, "ERROR: {e}"),
+        }
+
+        if io.prompt()? {
+            break;
+        }
+    }
+
+    Ok(())
+}
+
+// Sets the PIN of an account that has none yet, or resets a forgotten one
+fn set_account_pin(io: &mut Console<impl BufRead, impl Write>, bank: &mut Bank, storage: &Storage, admin: u64) -> Result<(), Interrupted> {
+    loop {
+        let user_input = io.input("\nSet Account PIN
+Account Number")?;
+
+        match user_input.parse::<u64>() {
+            Ok(number) if bank.get(number).is_some() => {
+                let pin = input_new_pin(io)?;
+
+                match bank.set_pin(admin, number, &pin) {
+                    Ok(()) => {
+                        save(io, bank, storage);
+                        say!(io, "PIN set for account {number}.");
+                    }
+                    Err(e) => say!(io, "ERROR: {e}"),
+                }
+            }
+            Ok(number) => say!(io, "ERROR: {}", BankError::AccountNotFound(number)),
+            Err(_) => say!(io, "ERROR: Input not valid."),
+        }
+
+        if io.prompt()? {
+            break;
+        }
+    }
+
+    Ok(())
+}
+
+fn rate_history(io: &mut Console<impl BufRead, impl Write>, bank: &Bank, registry: &CurrencyRegistry) -> Result<(), Interrupted> {
+    let mut user_input: String;
+    let currencies: Vec<&CurrencyInfo> = registry.all().iter().collect();
+
+    loop {
+        user_input = io.input("\nExchange Rate History
+[0] Back to the Main Menu
+[1] View Currency History
+[2] Rate at a Past Moment
+[3] Explain Transaction
+Select Option")?;
+
+        if user_input == "0" {
+            break;
+        } else if user_input == "1" {
+            let currency = match select_currency(&currencies, &io.input(&format!("\n{}Select Currency", currency_options(&currencies)))?) {
+                Some(c) => c.code,
+                None => {
+                    say!(io, "ERROR: Input not valid.");
+                    continue;
+                }
+            };
+            let history = bank.rate_history(currency);
+
+            say!(io, "ID | Effective | Pair | Before (Buy / Sell) | After (Buy / Sell) | Entered By |");
+            for transaction in &history {
+                if let TransactionKind::RateChange { from, to, before, after, entered_by } = &transaction.kind {
+                    say!(io, "{} | {} | {}/{} | {} | {} | {} |", transaction.id, transaction.timestamp.format("%Y-%m-%d %H:%M:%S"),
+                        from, to, before.map_or("None".to_string(), describe_rate), describe_rate(*after),
+                        entered_by.as_deref().unwrap_or("Unknown"));
+                }
+            }
+            if history.is_empty() {
+                say!(io, "No rate changes recorded for {currency}. Rates set up with the bank have always applied.");
+            }
+        } else if user_input == "2" {
+            let moment = match input_moment(io, "Moment (YYYY-MM-DD HH:MM, or YYYY-MM-DD for the end of that day)")? {
+                Some(moment) => moment,
+                None => continue,
+            };
+            let rates = bank.rates_at(moment);
+
+            say!(io, "Rates at {} (Buy / Sell):", moment.format("%Y-%m-%d %H:%M:%S"));
+            for (from, to, rate) in rates.pairs() {
+                say!(io, "1 {} = {} {}", from, describe_rate(rate), to);
+            }
+        } else if user_input == "3" {
+            let transaction = match io.input("Transaction ID")?.parse::<u64>().ok().and_then(|id| bank.transaction(id)) {
+                Some(transaction) => transaction,
+                None => {
+                    say!(io, "ERROR: Transaction not found.");
+                    continue;
+                }
+            };
+            explain_transaction(io, bank, registry, transaction);
+        } else {
+            say!(io, "ERROR: Input not valid.");
+        }
+    }
+
+    Ok(())
+}
+
+// Shows how the rate used by a conversion was made up from the rates in force at the time
+fn explain_transaction(io: &mut Console<impl BufRead, impl Write>, bank: &Bank, registry: &CurrencyRegistry, transaction: &Transaction) {
+    let source = |linked: u64| bank.transaction(linked).and_then(|t| t.kind.amounts()).map(|(amount, _, _)| amount.currency());
+    let (from, to, rate) = match transaction.kind {
+        TransactionKind::Exchange { before, after, rate } => (Some(before.currency()), after.currency(), rate),
+        TransactionKind::ExchangeIn { amount, rate, linked, .. }
+        | TransactionKind::TransferIn { amount, rate, linked, .. } => (source(linked), amount.currency(), rate),
+        TransactionKind::ExchangeOut { linked, .. } | TransactionKind::TransferOut { linked, .. } => {
+            say!(io, "Transaction {} is the debit side; explaining its credit, transaction {linked}.", transaction.id);
+            return explain_transaction(io, bank, registry, bank.transaction(linked).unwrap());
+        }
+        _ => {
+            say!(io, "ERROR: Transaction {} did not convert currencies.", transaction.id);
+            return;
+        }
+    };
+    let from = match from {
+        Some(from) if from != to => from,
+        _ => {
+            say!(io, "ERROR: Transaction {} did not convert currencies.", transaction.id);
+            return;
+        }
+    };
+
+    say!(io, "\nTransaction {} on {} converted {} to {} at 1 {} = {} {}", transaction.id,
+        transaction.timestamp.format("%Y-%m-%d %H:%M:%S"), registry.info(from), registry.info(to), from, format_rate(rate), to);
+
+    let rates = bank.rates_for(transaction.id);
+    match rates.quote(from, to) {
+        Some(conversion) => {
+            say!(io, "Conversion Path: {}", conversion.describe_path());
+            for pair in conversion.path.windows(2) {
+                match rates.pairs().find(|(f, t, _)| (*f, *t) == (pair[0], pair[1]) || (*f, *t) == (pair[1], pair[0])) {
+                    Some((f, t, r)) => say!(io, "  1 {} = {} {} (Buy / Sell)", f, describe_rate(r), t),
+                    None => say!(io, "  {} -> {}: no rate", pair[0], pair[1]),
+                }
+            }
+            say!(io, "Customer Rate Then: 1 {from} = {} {to}", format_rate(conversion.rate));
+            say!(io, "Mid Rate Then: 1 {from} = {} {to}", format_rate(conversion.mid_rate));
+        }
+        None => say!(io, "No rate from {from} to {to} was in force at the time."),
+    }
+}
+
+fn describe_rate(rate: PairRate) -> String {
+    format!("{} / {}", format_rate(rate.buy), format_rate(rate.sell))
+}
+
+// Date and time entered by the user; a date alone means the end of that day
+fn input_moment(io: &mut Console<impl BufRead, impl Write>, prompt: &str) -> Result<Option<NaiveDateTime>, Interrupted> {
+    let user_input = io.input(prompt)?;
+
+    match NaiveDateTime::parse_from_str(&user_input, "%Y-%m-%d %H:%M") {
+        Ok(moment) => Ok(Some(moment)),
+        Err(_) => match NaiveDate::parse_from_str(&user_input, "%Y-%m-%d") {
+            Ok(date) => Ok(date.and_hms_opt(23, 59, 59)),
+            Err(_) => {
+                say!(io, "ERROR: Enter a date and time as YYYY-MM-DD HH:MM or a date as YYYY-MM-DD.");
+                Ok(None)
+            }
+        },
+    }
+}
+
+// Menu lines generated from the currency registry, numbered from 0
+fn currency_options(currencies: &[&CurrencyInfo]) -> String {
+    currencies.iter().enumerate()
+        .map(|(i, c)| format!("[{i}] {c}\n"))
+        .collect()
+}
+
+fn select_currency<'a>(currencies: &[&'a CurrencyInfo], user_input: &str) -> Option<&'a CurrencyInfo> {
+    user_input.parse::<usize>().ok().and_then(|i| currencies.get(i).copied())
+}
+
+fn manage_currencies(io: &mut Console<impl BufRead, impl Write>, registry: &mut CurrencyRegistry, storage: &Storage) -> Result<(), Interrupted> {
+    let mut user_input: String;
+
+    loop {
+        say!(io, "\nManage Currencies
+Code | Name | Symbol | Minor Units | Status |");
+        for c in registry.all() {
+            say!(io, "{} | {} | {} | {} | {} |", c.code, c.name, c.symbol, c.minor_units,
+                if c.retired { "Retired" } else { "Active" });
+        }
+
+        user_input = io.input("\n[0] Back to the Main Menu
+[1] Add Currency
+[2] Retire Currency
+Select Option")?;
+
+        if user_input == "0" {
+            break;
+        } else if user_input == "1" {
+            let code = io.input("Currency Code (e.g. KRW)")?;
+            let name = io.input("Currency Name")?;
+            let symbol = io.input("Currency Symbol")?;
+            let minor_units = match io.input("Minor Units (decimal places)")?.parse::<u32>() {
+                Ok(minor_units) => minor_units,
+                Err(_) => {
+                    say!(io, "ERROR: Input not valid.");
+                    continue;
+                }
+            };
+
+            match registry.add(&code, &name, &symbol, minor_units) {
+                Ok(()) => say!(io, "Currency {code} added."),
+                Err(e) => {
+                    say!(io, "ERROR: {e}");
+                    continue;
+                }
+            }
+        } else if user_input == "2" {
+            let currencies = registry.active();
+            user_input = io.input(&format!("\n{}Select Currency to Retire", currency_options(&currencies)))?;
+
+            let code = match select_currency(&currencies, &user_input) {
+                Some(c) => c.code,
+                None => {
+                    say!(io, "ERROR: Input not valid.");
+                    continue;
+                }
+            };
+
+            match registry.retire(code) {
+                Ok(()) => say!(io, "Currency {code} retired."),
+                Err(e) => {
+                    say!(io, "ERROR: {e}");
+                    continue;
+                }
+            }
+        } else {
+            say!(io, "ERROR: Input not valid.");
+            continue;
+        }
+
+        save(io, registry, storage);
+    }
+
+    Ok(())
+}
+
+fn exchange_fees(io: &mut Console<impl BufRead, impl Write>, bank: &mut Bank, registry: &CurrencyRegistry, storage: &Storage) -> Result<(), Interrupted> {
+    let mut user_input: String;
+    let currencies = registry.active();
+
+    loop {
+        say!(io, "\nExchange Fees
+Default Fee: {}
+Currency | Fee |", format_percent(bank.fees.default_percentage));
+        for (currency, rule) in bank.fees.rules() {
+            say!(io, "{} | {} |", currency, describe_fee(registry, rule));
+        }
+
+        user_input = io.input("\n[0] Back to the Main Menu
+[1] Set Default Percentage
+[2] Set Currency Percentage
+[3] Set Currency Flat Fee
+[4] Remove Currency Fee
+Select Option")?;
+
+        if user_input == "0" {
+            break;
+        } else if user_input == "1" {
+            match input_percent(io, "Default Fee (%)")? {
+                Some(rate) => bank.fees.default_percentage = rate,
+                None => continue,
+            }
+        } else if user_input == "2" || user_input == "3" || user_input == "4" {
+            let currency = match select_currency(&currencies, &io.input(&format!("\n{}Select Currency", currency_options(&currencies)))?) {
+                Some(c) => c,
+                None => {
+                    say!(io, "ERROR: Input not valid.");
+                    continue;
+                }
+            };
+
+            if user_input == "2" {
+                match input_percent(io, "Fee (%)")? {
+                    Some(rate) => bank.fees.set(currency.code, FeeRule::Percentage(rate)),
+                    None => continue,
+                }
+            } else if user_input == "3" {
+                match Money::parse(&io.input("Flat Fee")?, currency) {
+                    Ok(fee) if !fee.is_positive() => {
+                        say!(io, "ERROR: Flat fee must be greater than 0.");
+                        continue;
+                    }
+                    Ok(fee) => bank.fees.set(currency.code, FeeRule::Flat(fee)),
+                    Err(e) => {
+                        say!(io, "ERROR: {e}");
+                        continue;
+                    }
+                }
+            } else if !bank.fees.remove(currency.code) {
+                say!(io, "ERROR: {} uses the default fee already.", currency.code);
+                continue;
+            }
+        } else {
+            say!(io, "ERROR: Input not valid.");
+            continue;
+        }
+
+        save(io, bank, storage);
+    }
+
+    Ok(())
+}
+
+fn describe_fee(registry: &CurrencyRegistry, rule: FeeRule) -> String {
+    match rule {
+        FeeRule::Percentage(rate) => format_percent(rate),
+        FeeRule::Flat(fee) => format!("{} flat", registry.format(fee)),
+    }
+}
+
+// Percentage from 0 to 100 entered by the user, returned as a fraction
+fn input_percent(io: &mut Console<impl BufRead, impl Write>, prompt: &str) -> Result<Option<f64>, Interrupted> {
+    match io.input(prompt)?.parse::<f64>() {
+        Ok(percent) if (0.0..=100.0).contains(&percent) => Ok(Some(percent / 100.0)),
+        _ => {
+            say!(io, "ERROR: Enter a percentage from 0 to 100.");
+            Ok(None)
+        }
+    }
+}
+
+fn income_report(io: &mut Console<impl BufRead, impl Write>, bank: &Bank, registry: &CurrencyRegistry) -> Result<(), Interrupted> {
+    say!(io, "\nIncome Report");
+    let start = input_date(io, "Start Date (YYYY-MM-DD, blank for none)")?;
+    let end = input_date(io, "End Date (YYYY-MM-DD, blank for none)")?;
+    let report = fees::income_report(bank.income(), start, end);
+
+    say!(io, "Source | Currency | Income |");
+    for ((kind, currency), total) in &report {
+        say!(io, "{} | {} | {} |", kind.name(), currency, registry.format(*total));
+    }
+    if report.is_empty() {
+        say!(io, "No income recorded.");
+    }
+
+    Ok(())
+}
+
+fn show_interest(io: &mut Console<impl BufRead, impl Write>, bank: &Bank, registry: &CurrencyRegistry, number: u64) -> Result<(), Interrupted> {
+    let mut user_input: String;
+    let mut currency: &CurrencyInfo;
+    let mut periods: u32;
+    let account = bank.get(number).unwrap();
+    let tax_rate = bank.withholding_tax_rate;
+    let settings = account.interest;
+    let compounding = settings.compounding;
+    let max_periods = MAX_PROJECTION_YEARS * compounding.periods_per_year();
+    let currencies = funded_wallets(account, registry);
+
+    loop {
+        say!(io, "\nShow Interest Amount
+Account Number: {}
+Account Name: {}", account.number, account.name);
+
+        currency = match select_wallet(io, account, &currencies)? {
+            Some(currency) => currency,
+            None => {
+                say!(io, "ERROR: Input not valid.");
+                continue;
+            }
+        };
+        say!(io, "Current Balance: {}
+Currency: {}
+Interest Rate: {}
+Compounding: {}
+Day Count: {}
+Withholding Tax: {}", registry.format(account.balance(currency)), currency,
+            format_percent(settings.annual_rate), compounding.name(), settings.day_count.name(), format_percent(tax_rate));
+        let start = input_date(io, "Start Date (YYYY-MM-DD, blank for the business date)")?.unwrap_or(bank.business_date());
+        user_input = io.input(&format!("Total Number of {}s", compounding.period_name()))?;
+
+        if user_input.parse::<u32>().is_err() {
+            say!(io, "ERROR: Input not valid.");
+            continue;
+        }
+
+        periods = user_input.parse::<u32>().unwrap();
+
+        if periods == 0 || periods > max_periods {
+            say!(io, "ERROR: {}s must be greater than 0 and at most {} ({} years).",
+                compounding.period_name(), max_periods, MAX_PROJECTION_YEARS);
+            continue;
+        }
+
+        say!(io, "{} | Start | End | Principal | Gross Interest | Tax Withheld | Net Interest | Balance |", compounding.period_name());
+        for row in interest::project(account.balance(currency), settings, tax_rate, start, periods) {
+            say!(io, "{} | {} | {} | {} | {} | {} | {} | {} |", row.period, row.start, row.end, registry.format(row.principal),
+                registry.format(row.gross), registry.format(row.tax), registry.format(row.net), registry.format(row.balance));
+        }
+
+        if io.prompt()? {
+            break;
+        }
+    }
+
+    Ok(())
+}
+
+fn interest_settings(io: &mut Console<impl BufRead, impl Write>, bank: &mut Bank, storage: &Storage) -> Result<(), Interrupted> {
+    let mut user_input: String;
+    let mut annual_rate: f64;
+    let mut compounding: Compounding;
+    let mut day_count: DayCount;
+
+    loop {
+        user_input = io.input("\nInterest Settings
+Account Number")?;
+
+        let Ok(number) = user_input.parse::<u64>() else {
+            say!(io, "ERROR: Input not valid.");
+            continue;
+        };
+        let Some(account) = bank.get(number) else {
+            say!(io, "ERROR: {}", BankError::AccountNotFound(number));
+            continue;
+        };
+
+        let settings = account.interest;
+        say!(io, "Account Name: {}
+Interest Rate: {}
+Compounding: {}
+Day Count: {}", account.name, format_percent(settings.annual_rate), settings.compounding.name(), settings.day_count.name());
+        user_input = io.input("Annual Interest Rate (%)")?;
+
+        match user_input.parse::<f64>() {
+            Ok(rate) if (0.0..=100.0).contains(&rate) => annual_rate = rate / 100.0,
+            Ok(_) => {
+                say!(io, "ERROR: Interest rate must be between 0 and 100%.");
+                continue;
+            }
+            Err(_) => {
+                say!(io, "ERROR: Input not valid.");
+                continue;
+            }
+        }
+
+        user_input = io.input(&format!("\n{}Compounding", Compounding::ALL.iter().enumerate()
+            .map(|(i, c)| format!("[{i}] {}\n", c.name()))
+            .collect::<String>()))?;
+
+        match user_input.parse::<usize>().ok().and_then(|i| Compounding::ALL.get(i)) {
+            Some(c) => compounding = *c,
+            None => {
+                say!(io, "ERROR: Input not valid.");
+                continue;
+            }
+        }
+
+        user_input = io.input(&format!("\n{}Day Count", DayCount::ALL.iter().enumerate()
+            .map(|(i, d)| format!("[{i}] {}\n", d.name()))
+            .collect::<String>()))?;
+
+        match user_input.parse::<usize>().ok().and_then(|i| DayCount::ALL.get(i)) {
+            Some(d) => day_count = *d,
+            None => {
+                say!(io, "ERROR: Input not valid.");
+                continue;
+            }
+        }
+
+        bank.get_mut(number).unwrap().interest = InterestSettings { annual_rate, compounding, day_count };
+        save(io, bank, storage);
+        say!(io, "Updated Interest: {} compounded {}, {}", format_percent(annual_rate),
+            compounding.name().to_lowercase(), day_count.name());
+
+        if io.prompt()? {
+            break;
+        }
+    }
+
+    Ok(())
+}
+
+fn end_of_day(io: &mut Console<impl BufRead, impl Write>, bank: &mut Bank, registry: &CurrencyRegistry, storage: &Storage) -> Result<(), Interrupted> {
+    let mut user_input: String;
+    let mut days: u32;
+
+    loop {
+        say!(io, "\nEnd of Day Processing
+Business Date: {}", bank.business_date());
+        user_input = io.input("Number of Business Days to Process")?;
+
+        match user_input.parse::<u32>() {
+            Ok(n) if (1..=MAX_END_OF_DAY_RUN).contains(&n) => days = n,
+            Ok(_) => {
+                say!(io, "ERROR: Business days must be between 1 and {MAX_END_OF_DAY_RUN}.");
+                continue;
+            }
+            Err(_) => {
+                say!(io, "ERROR: Input not valid.");
+                continue;
+            }
+        }
+
+        say!(io, "Date | Account Number | Gross Interest | Tax Withheld | Net Interest | Balance |");
+        for _ in 0..days {
+            for id in bank.end_of_day(registry) {
+                let transaction = bank.transaction(id).unwrap();
+                let (gross, tax) = transaction.kind.withholding().unwrap();
+                let (net, _, after) = transaction.kind.amounts().unwrap();
+
+                say!(io, "{} | {} | {} | {} | {} | {} |", transaction.timestamp.date(), transaction.account.unwrap(),
+                    registry.format(gross), registry.format(tax), registry.format(net), registry.format(after));
+            }
+        }
+        save(io, bank, storage);
+        say!(io, "Business Date: {}", bank.business_date());
+
+        if io.prompt()? {
+            break;
+        }
+    }
+
+    Ok(())
+}
+
+fn tax_summary(io: &mut Console<impl BufRead, impl Write>, bank: &Bank, registry: &CurrencyRegistry, number: u64) -> Result<(), Interrupted> {
+    let account = bank.get(number).unwrap();
+
+    say!(io, "\nYear-End Tax Summary
+Account Number: {}
+Account Name: {}", account.number, account.name);
+    let year = match io.input("Year (YYYY, blank for the business year)")? {
+        user_input if user_input.is_empty() => bank.business_date().year(),
+        user_input => match user_input.parse::<i32>() {
+            Ok(year) if (1..=9999).contains(&year) => year,
+            _ => {
+                say!(io, "ERROR: Input not valid.");
+                return Ok(());
+            }
+        },
+    };
+    let summary = bank.tax_summary(number, year);
+
+    say!(io, "\nInterest Posted in {year}
+Currency | Gross Interest | Tax Withheld | Net Interest |");
+    for (currency, (gross, tax, net)) in &summary {
+        say!(io, "{} | {} | {} | {} |", currency, registry.format(*gross), registry.format(*tax), registry.format(*net));
+    }
+    if summary.is_empty() {
+        say!(io, "No interest posted.");
+    }
+
+    Ok(())
+}
+
+fn update_withholding_tax(io: &mut Console<impl BufRead, impl Write>, bank: &mut Bank, storage: &Storage) -> Result<(), Interrupted> {
+    let mut user_input: String;
+
+    loop {
+        say!(io, "\nWithholding Tax Rate
+Current Rate: {}", format_percent(bank.withholding_tax_rate));
+        user_input = io.input("Withholding Tax Rate (%)")?;
+
+        match user_input.parse::<f64>() {
+            Ok(rate) if (0.0..=100.0).contains(&rate) => bank.withholding_tax_rate = rate / 100.0,
+            Ok(_) => {
+                say!(io, "ERROR: Withholding tax rate must be between 0 and 100%.");
+                continue;
+            }
+            Err(_) => {
+                say!(io, "ERROR: Input not valid.");
+                continue;
+            }
+        }
+
+        save(io, bank, storage);
+        say!(io, "Updated Withholding Tax Rate: {}", format_percent(bank.withholding_tax_rate));
+
+        if io.prompt()? {
+            break;
+        }
+    }
+
+    Ok(())
+}
+
+fn format_percent(rate: f64) -> String {
+    format!("{}%", format_rate(rate * 100.0))
+}
+
+fn input_date(io: &mut Console<impl BufRead, impl Write>, prompt: &str) -> Result<Option<NaiveDate>, Interrupted> {
+    let mut user_input: String;
+
+    loop {
+        user_input = io.input(prompt)?;
+
+        if user_input.is_empty() {
+            return Ok(None);
+        }
+
+        match NaiveDate::parse_from_str(&user_input, "%Y-%m-%d") {
+            Ok(date) => return Ok(Some(date)),
+            Err(_) => say!(io, "ERROR: Enter a date as YYYY-MM-DD or leave it blank."),
+        }
+    }
+}
+
+fn view_statement(io: &mut Console<impl BufRead, impl Write>, bank: &Bank, registry: &CurrencyRegistry, number: u64) -> Result<(), Interrupted> {
+    let mut user_input: String;
+    let mut page: usize = 0;
+    let account = bank.get(number).unwrap();
+
+    say!(io, "\nView Statement
+Account Number: {}
+Account Name: {}", account.number, account.name);
+    let start = input_date(io, "Start Date (YYYY-MM-DD, blank for none)")?;
+    let end = input_date(io, "End Date (YYYY-MM-DD, blank for none)")?;
+    let transactions = bank.statement(number, start, end);
+    let page_count = transactions.len().div_ceil(STATEMENT_PAGE_SIZE).max(1);
+
+    loop {
+        say!(io, "\nStatement Page {} of {}
+ID | Date | Type | Amount | Balance Before | Balance After |", page + 1, page_count);
+        for transaction in transactions.iter().skip(page * STATEMENT_PAGE_SIZE).take(STATEMENT_PAGE_SIZE) {
+            if let Some((amount, before, after)) = transaction.kind.amounts() {
+                say!(io, "{} | {} | {} | {} | {} | {} |",
+                    transaction.id, transaction.timestamp.format("%Y-%m-%d %H:%M"), transaction.kind.name(),
+                    registry.format(amount), registry.format(before), registry.format(after));
+            }
+            if let Some((gross, tax)) = transaction.kind.withholding() {
+                say!(io, "  Gross Interest: {} | Tax Withheld: {} | Net Interest: {}",
+                    registry.format(gross), registry.format(tax), registry.format(gross - tax));
+            }
+            match transaction.kind {
+                TransactionKind::TransferOut { to, linked, .. } => say!(io, "  To Account: {to} | Linked Transaction: {linked}"),
+                TransactionKind::TransferIn { from, linked, rate, .. } => {
+                    say!(io, "  From Account: {from} | Linked Transaction: {linked} | Rate: {}", format_rate(rate))
+                }
+                TransactionKind::ExchangeOut { to, linked, fee, .. } => {
+                    say!(io, "  To Wallet: {to} | Linked Transaction: {linked}{}",
+                        fee.map_or(String::new(), |fee| format!(" | Fee: {}", registry.format(fee))))
+                }
+                TransactionKind::ExchangeIn { linked, rate, .. } => {
+                    say!(io, "  Linked Transaction: {linked} | Rate: {}", format_rate(rate))
+                }
+                _ => {}
+            }
+        }
+        if transactions.is_empty() {
+            say!(io, "No transactions found.");
+        }
+
+        user_input = io.input("\n[N] Next Page
+[P] Previous Page
+[E] Export CSV
+[B] Back to the Main Menu
+Select Option")?;
+
+        if user_input == "N" && page + 1 < page_count {
+            page += 1;
+        } else if user_input == "N" {
+            say!(io, "ERROR: Already on the last page.");
+        } else if user_input == "P" && page > 0 {
+            page -= 1;
+        } else if user_input == "P" {
+            say!(io, "ERROR: Already on the first page.");
+        } else if user_input == "E" {
+            export_statement(io, number, start, end, &transactions);
+        } else if user_input == "B" {
+            break;
+        } else {
+            say!(io, "ERROR: Input not valid.");
+        }
+    }
+
+    Ok(())
+}
+
+fn export_statement(io: &mut Console<impl BufRead, impl Write>, number: u64, start: Option<NaiveDate>, end: Option<NaiveDate>, transactions: &[&Transaction]) {
+    let path = format!("{STATEMENT_DIR}/statement_{number}_{}_{}.csv",
+        start.map_or("start".to_string(), |d| d.to_string()),
+        end.map_or("end".to_string(), |d| d.to_string()));
+    let mut contents = String::from(CSV_HEADER);
+
+    for transaction in transactions {
+        contents.push('\n');
+        contents.push_str(&transaction.to_csv());
+    }
+    contents.push('\n');
+
+    match fs::create_dir_all(STATEMENT_DIR).and_then(|_| fs::write(&path, contents)) {
+        Ok(_) => say!(io, "Statement exported to {path}"),
+        Err(e) => say!(io, "ERROR: Could not export statement to {path}: {e}"),
+    }
+}
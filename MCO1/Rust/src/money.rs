@@ -0,0 +1,173 @@
+use core::fmt;
+use std::cmp::Ordering;
+use std::ops::{Add, AddAssign, Sub, SubAssign};
+
+use serde::{Deserialize, Serialize};
+
+use crate::currency::{Currency, CurrencyInfo};
+
+// Fixed-point amount stored in the currency's minor units (e.g. centavos for PHP)
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Serialize, Deserialize)]
+pub struct Money {
+    minor: i64,
+    currency: Currency,
+    minor_units: u32,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum ParseMoneyError {
+    Invalid,
+    TooManyDecimals(u32),
+}
+
+impl fmt::Display for ParseMoneyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseMoneyError::Invalid => write!(f, "Input not valid."),
+            ParseMoneyError::TooManyDecimals(0) => write!(f, "Amount must be a whole number."),
+            ParseMoneyError::TooManyDecimals(units) => write!(f, "Amount must have at most {units} decimal places."),
+        }
+    }
+}
+
+impl Money {
+    pub fn zero(currency: &CurrencyInfo) -> Money {
+        Money::from_minor(0, currency)
+    }
+
+    pub fn from_minor(minor: i64, currency: &CurrencyInfo) -> Money {
+        Money { minor, currency: currency.code, minor_units: currency.minor_units }
+    }
+
+    pub fn from_major(major: i64, currency: &CurrencyInfo) -> Money {
+        Money::from_minor(major * 10_i64.pow(currency.minor_units), currency)
+    }
+
+    // Rounds an amount in major units, such as accrued interest, to the currency's minor units
+    pub fn from_f64(major: f64, currency: &CurrencyInfo) -> Money {
+        Money::from_minor((major * 10_f64.powi(currency.minor_units as i32)).round() as i64, currency)
+    }
+
+    // Parses a plain decimal like "1234.5"; exponents, NaN and extra decimal places are rejected
+    pub fn parse(input: &str, currency: &CurrencyInfo) -> Result<Money, ParseMoneyError> {
+        let units = currency.minor_units;
+        let (is_negative, digits) = match input.strip_prefix('-') {
+            Some(rest) => (true, rest),
+            None => (false, input.strip_prefix('+').unwrap_or(input)),
+        };
+        let (whole, fraction) = digits.split_once('.').unwrap_or((digits, ""));
+
+        if whole.is_empty() && fraction.is_empty()
+            || !whole.chars().all(|c| c.is_ascii_digit())
+            || !fraction.chars().all(|c| c.is_ascii_digit()) {
+            return Err(ParseMoneyError::Invalid);
+        }
+
+        let fraction = fraction.trim_end_matches('0');
+        if fraction.len() > units as usize {
+            return Err(ParseMoneyError::TooManyDecimals(units));
+        }
+
+        let mut minor: i64 = 0;
+        for c in whole.chars().chain(fraction.chars()) {
+            minor = minor.checked_mul(10)
+                .and_then(|m| m.checked_add(c.to_digit(10).unwrap() as i64))
+                .ok_or(ParseMoneyError::Invalid)?;
+        }
+        minor = minor.checked_mul(10_i64.pow(units - fraction.len() as u32))
+            .ok_or(ParseMoneyError::Invalid)?;
+
+        Ok(Money::from_minor(if is_negative { -minor } else { minor }, currency))
+    }
+
+    pub fn currency(&self) -> Currency {
+        self.currency
+    }
+
+    pub fn minor_units(&self) -> u32 {
+        self.minor_units
+    }
+
+    pub fn is_positive(&self) -> bool {
+        self.minor > 0
+    }
+
+    pub fn to_f64(self) -> f64 {
+        self.minor as f64 / 10_f64.powi(self.minor_units as i32)
+    }
+
+    // None when the sum does not fit, as amounts typed in can be far beyond any balance
+    pub fn checked_add(self, other: Money) -> Option<Money> {
+        assert_eq!(self.currency, other.currency, "cannot add amounts in different currencies");
+        self.minor.checked_add(other.minor).map(|minor| Money { minor, ..self })
+    }
+
+    pub fn checked_sub(self, other: Money) -> Option<Money> {
+        assert_eq!(self.currency, other.currency, "cannot subtract amounts in different currencies");
+        self.minor.checked_sub(other.minor).map(|minor| Money { minor, ..self })
+    }
+
+    // Multiplies by a factor such as an interest rate, rounding half away from zero
+    pub fn multiply(self, factor: f64) -> Money {
+        Money { minor: (self.minor as f64 * factor).round() as i64, ..self }
+    }
+
+    // Converts into another currency where one unit of self is worth `rate` units of `to`
+    pub fn convert(self, to: &CurrencyInfo, rate: f64) -> Money {
+        Money::from_minor((self.to_f64() * rate * 10_f64.powi(to.minor_units as i32)).round() as i64, to)
+    }
+}
+
+impl PartialOrd for Money {
+    // Amounts in different currencies are not comparable
+    fn partial_cmp(&self, other: &Money) -> Option<Ordering> {
+        if self.currency != other.currency {
+            return None;
+        }
+
+        Some(self.minor.cmp(&other.minor))
+    }
+}
+
+impl Add for Money {
+    type Output = Money;
+
+    fn add(self, other: Money) -> Money {
+        self.checked_add(other).expect("amount is out of range")
+    }
+}
+
+impl Sub for Money {
+    type Output = Money;
+
+    fn sub(self, other: Money) -> Money {
+        self.checked_sub(other).expect("amount is out of range")
+    }
+}
+
+impl AddAssign for Money {
+    fn add_assign(&mut self, other: Money) {
+        *self = *self + other;
+    }
+}
+
+impl SubAssign for Money {
+    fn sub_assign(&mut self, other: Money) {
+        *self = *self - other;
+    }
+}
+
+impl fmt::Display for Money {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let units = self.minor_units;
+        let scale = 10_u64.pow(units);
+        let sign = if self.minor < 0 { "-" } else { "" };
+        let minor = self.minor.unsigned_abs();
+
+        if units == 0 {
+            write!(f, "{sign}{minor}")
+        } else {
+            write!(f, "{sign}{}.{:0width$}", minor / scale, minor % scale, width = units as usize)
+        }
+    }
+}
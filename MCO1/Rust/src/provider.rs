@@ -0,0 +1,136 @@
+use std::io::{Read, Write};
+use std::net::{TcpStream, ToSocketAddrs};
+use std::path::PathBuf;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use crate::currency::{Currency, CurrencyRegistry};
+use crate::import::{self, RateSheet, RowError};
+use crate::rates::{PairRate, RateTable};
+
+const HTTP_TIMEOUT: Duration = Duration::from_secs(5);
+
+// Source of exchange rates; the rates it returns are checked the same way however they were obtained
+pub trait RateProvider {
+    // Shown as who entered the rates in the rate history
+    fn name(&self) -> String;
+
+    fn fetch(&self, registry: &CurrencyRegistry, rates: &RateTable) -> Result<RateSheet, String>;
+}
+
+// Rates typed in by a teller
+pub struct ManualProvider {
+    pub entered_by: String,
+    pub rates: Vec<(Currency, Currency, PairRate)>,
+}
+
+impl RateProvider for ManualProvider {
+    fn name(&self) -> String {
+        self.entered_by.clone()
+    }
+
+    fn fetch(&self, registry: &CurrencyRegistry, rates: &RateTable) -> Result<RateSheet, String> {
+        let mut rows = Vec::new();
+        let mut errors = Vec::new();
+
+        for (i, &(from, to, rate)) in self.rates.iter().enumerate() {
+            match import::check_rate(i + 1, from, to, rate, registry, rates, &rows) {
+                Ok(row) => rows.push(row),
+                Err(message) => errors.push(RowError { line: i + 1, message }),
+            }
+        }
+
+        Ok((rows, errors))
+    }
+}
+
+// CSV or JSON rate sheet on disk, reread on every refresh
+pub struct FileProvider {
+    pub path: PathBuf,
+}
+
+impl RateProvider for FileProvider {
+    fn name(&self) -> String {
+        format!("File {}", self.path.display())
+    }
+
+    fn fetch(&self, registry: &CurrencyRegistry, rates: &RateTable) -> Result<RateSheet, String> {
+        import::read_rate_sheet(&self.path, registry, rates)
+    }
+}
+
+// Endpoint answering a GET with a JSON rate sheet. Only plain http:// is supported.
+pub struct HttpProvider {
+    pub url: String,
+}
+
+impl RateProvider for HttpProvider {
+    fn name(&self) -> String {
+        format!("HTTP {}", self.url)
+    }
+
+    fn fetch(&self, registry: &CurrencyRegistry, rates: &RateTable) -> Result<RateSheet, String> {
+        let body = http_get(&self.url)?;
+
+        import::parse_rate_sheet(&body, true, registry, rates)
+    }
+}
+
+fn http_get(url: &str) -> Result<String, String> {
+    let rest = url.strip_prefix("http://").ok_or("URL must start with http://")?;
+    let (host, path) = match rest.find('/') {
+        Some(i) => (&rest[..i], &rest[i..]),
+        None => (rest, "/"),
+    };
+    let address = if host.contains(':') { host.to_string() } else { format!("{host}:80") };
+    let error = |e: std::io::Error| format!("Could not fetch {url}: {e}");
+
+    let socket = address.to_socket_addrs().map_err(error)?.next().ok_or(format!("Could not resolve {host}"))?;
+    let mut stream = TcpStream::connect_timeout(&socket, HTTP_TIMEOUT).map_err(error)?;
+    stream.set_read_timeout(Some(HTTP_TIMEOUT)).map_err(error)?;
+    write!(stream, "GET {path} HTTP/1.1\r\nHost: {host}\r\nAccept: application/json\r\nConnection: close\r\n\r\n").map_err(error)?;
+
+    let mut response = String::new();
+    stream.read_to_string(&mut response).map_err(error)?;
+
+    let (head, body) = response.split_once("\r\n\r\n").ok_or(format!("Malformed response from {url}"))?;
+    let status = head.lines().next().unwrap_or_default();
+
+    if status.split_whitespace().nth(1) != Some("200") {
+        return Err(format!("{url} answered {status:?}"));
+    } else if head.to_ascii_lowercase().contains("transfer-encoding: chunked") {
+        return Err(format!("{url} sent a chunked response, which is not supported"));
+    }
+
+    Ok(body.to_string())
+}
+
+// Provider chosen by an administrator, saved with the bank
+#[derive(Serialize, Deserialize, Clone, Default)]
+pub enum ProviderConfig {
+    // Rates are only entered through the menu
+    #[default]
+    Manual,
+    File(PathBuf),
+    Http(String),
+}
+
+impl ProviderConfig {
+    // None when rates are only entered by hand, as there is nothing to refresh from
+    pub fn provider(&self) -> Option<Box<dyn RateProvider>> {
+        match self {
+            ProviderConfig::Manual => None,
+            ProviderConfig::File(path) => Some(Box::new(FileProvider { path: path.clone() })),
+            ProviderConfig::Http(url) => Some(Box::new(HttpProvider { url: url.clone() })),
+        }
+    }
+
+    pub fn describe(&self) -> String {
+        match self {
+            ProviderConfig::Manual => "Manual entry".to_string(),
+            ProviderConfig::File(path) => format!("Rate sheet file {}", path.display()),
+            ProviderConfig::Http(url) => format!("HTTP endpoint {url}"),
+        }
+    }
+}
@@ -0,0 +1,255 @@
+use std::collections::{BTreeMap, VecDeque};
+
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+
+use crate::currency::Currency;
+use crate::error::BankError;
+
+// Rates the bank deals at for a pair, as how many of the second currency one of the first is worth.
+// The bank buys the first currency at the buy rate and sells it at the higher sell rate.
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Debug)]
+#[serde(from = "StoredRate")]
+pub struct PairRate {
+    pub buy: f64,
+    pub sell: f64,
+}
+
+// Rate as saved, which may still be the single mid rate recorded before buy and sell rates were kept
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum StoredRate {
+    Mid(f64),
+    Spread { buy: f64, sell: f64 },
+}
+
+impl From<StoredRate> for PairRate {
+    fn from(stored: StoredRate) -> PairRate {
+        match stored {
+            StoredRate::Mid(rate) => PairRate { buy: rate, sell: rate },
+            StoredRate::Spread { buy, sell } => PairRate { buy, sell },
+        }
+    }
+}
+
+// Highest rate that may be recorded for a pair
+pub const MAX_RATE: f64 = 100_000.0;
+// Rate changes larger than this share of the previous mid rate are flagged
+pub const DEFAULT_MAX_RATE_MOVE: f64 = 0.10;
+// Rate changes larger than this share of the previous mid rate wait for a second administrator
+pub const DEFAULT_APPROVAL_THRESHOLD: f64 = 0.05;
+// Highest approval threshold, so one administrator cannot raise it until their own changes need no approval
+pub const MAX_APPROVAL_THRESHOLD: f64 = 0.10;
+// Round trips gaining less than this are put down to floating point error
+const ARBITRAGE_TOLERANCE: f64 = 1e-6;
+
+impl PairRate {
+    pub fn mid(self) -> f64 {
+        (self.buy + self.sell) / 2.0
+    }
+
+    // Relative change of the mid rate from an earlier rate, e.g. 0.05 for a 5% rise
+    pub fn change_from(self, before: PairRate) -> f64 {
+        self.mid() / before.mid() - 1.0
+    }
+
+    // Checks a rate before it is recorded, however it was entered
+    pub fn validate(self) -> Result<(), BankError> {
+        if self.buy <= 0.0 || self.sell > MAX_RATE || !self.buy.is_finite() || !self.sell.is_finite() {
+            Err(BankError::RateOutOfRange)
+        } else if self.buy > self.sell {
+            Err(BankError::BuyAboveSell)
+        } else {
+            Ok(())
+        }
+    }
+}
+
+// Exchange rates keyed by pair, where rates[from][to] is how many `to` one `from` is worth
+#[derive(Serialize, Deserialize, Clone)]
+pub struct RateTable {
+    rates: BTreeMap<Currency, BTreeMap<Currency, PairRate>>,
+    // Bumped on every change so quotes made before it can be recognised as stale
+    #[serde(default)]
+    version: u64,
+    // When each pair was last recorded or confirmed unchanged by a rate provider
+    #[serde(default)]
+    as_of: BTreeMap<Currency, BTreeMap<Currency, NaiveDateTime>>,
+}
+
+// Rate found for a pair together with the currencies it was routed through
+pub struct Conversion {
+    // Rate the customer gets, after the spread on every pair along the path
+    pub rate: f64,
+    pub mid_rate: f64,
+    pub path: Vec<Currency>,
+}
+
+// Rate with up to 6 decimal places and no trailing zeros, e.g. "0.017" or "152.941176"
+pub fn format_rate(rate: f64) -> String {
+    let formatted = format!("{rate:.6}");
+
+    formatted.trim_end_matches('0').trim_end_matches('.').to_string()
+}
+
+// Round trip through two or three currencies that ends with more than it started, at customer rates
+pub struct ArbitrageLoop {
+    pub path: Vec<Currency>,
+    // What one unit of the first currency turns into, e.g. 1.02 for a 2% gain
+    pub result: f64,
+}
+
+impl ArbitrageLoop {
+    pub fn describe(&self) -> String {
+        let path: Vec<&str> = self.path.iter().map(|c| c.as_str()).collect();
+
+        format!("{} gains {:.4}%", path.join(" -> "), (self.result - 1.0) * 100.0)
+    }
+}
+
+impl Conversion {
+    pub fn describe_path(&self) -> String {
+        let path: Vec<&str> = self.path.iter().map(|c| c.as_str()).collect();
+
+        if self.path.len() == 2 {
+            format!("{} (direct)", path.join(" -> "))
+        } else {
+            format!("{} (cross rate)", path.join(" -> "))
+        }
+    }
+}
+
+impl Default for RateTable {
+    fn default() -> RateTable {
+        RateTable::new()
+    }
+}
+
+impl RateTable {
+    pub fn new() -> RateTable {
+        RateTable { rates: BTreeMap::new(), version: 0, as_of: BTreeMap::new() }
+    }
+
+    // Returns the previously recorded rate for the pair, if any
+    pub fn set(&mut self, from: Currency, to: Currency, rate: PairRate) -> Option<PairRate> {
+        self.version += 1;
+        self.rates.entry(from).or_default().insert(to, rate)
+    }
+
+    pub fn remove(&mut self, from: Currency, to: Currency) -> Option<PairRate> {
+        self.version += 1;
+        self.rates.get_mut(&from).and_then(|r| r.remove(&to))
+    }
+
+    pub fn touch(&mut self, from: Currency, to: Currency, at: NaiveDateTime) {
+        self.as_of.entry(from).or_default().insert(to, at);
+    }
+
+    // When the pair was last known to be current, in either direction; None for rates set up with the bank
+    pub fn as_of(&self, from: Currency, to: Currency) -> Option<NaiveDateTime> {
+        self.as_of.get(&from).and_then(|r| r.get(&to))
+            .or_else(|| self.as_of.get(&to).and_then(|r| r.get(&from)))
+            .copied()
+    }
+
+    pub fn version(&self) -> u64 {
+        self.version
+    }
+
+    // Rate recorded for the pair exactly as given, without trying the opposite pair
+    pub fn get(&self, from: Currency, to: Currency) -> Option<PairRate> {
+        self.rates.get(&from).and_then(|r| r.get(&to)).copied()
+    }
+
+    // Customer and mid rates for converting along one recorded pair, in either direction.
+    // Converting into the second currency of a pair gets the buy rate; converting back pays the sell rate.
+    fn direct(&self, from: Currency, to: Currency) -> Option<(f64, f64)> {
+        self.rates.get(&from).and_then(|r| r.get(&to)).map(|rate| (rate.buy, rate.mid()))
+            .or_else(|| self.rates.get(&to).and_then(|r| r.get(&from)).map(|rate| (1.0 / rate.sell, 1.0 / rate.mid())))
+    }
+
+    // Uses the direct pair when recorded, otherwise chains the fewest pairs that connect the currencies
+    pub fn quote(&self, from: Currency, to: Currency) -> Option<Conversion> {
+        let mut previous: BTreeMap<Currency, Currency> = BTreeMap::new();
+        let mut queue: VecDeque<Currency> = VecDeque::from([from]);
+
+        while let Some(current) = queue.pop_front() {
+            if current == to {
+                break;
+            }
+
+            for next in self.neighbours(current) {
+                if next != from && !previous.contains_key(&next) {
+                    previous.insert(next, current);
+                    queue.push_back(next);
+                }
+            }
+        }
+
+        let mut path = vec![to];
+        while *path.last().unwrap() != from {
+            path.push(*previous.get(path.last().unwrap())?);
+        }
+        path.reverse();
+
+        let (rate, mid_rate) = path.windows(2)
+            .map(|pair| self.direct(pair[0], pair[1]).unwrap())
+            .fold((1.0, 1.0), |(rate, mid_rate), (leg, mid_leg)| (rate * leg, mid_rate * mid_leg));
+
+        Some(Conversion { rate, mid_rate, path })
+    }
+
+    fn neighbours(&self, currency: Currency) -> Vec<Currency> {
+        let mut neighbours: Vec<Currency> = self.rates.get(&currency)
+            .map(|r| r.keys().copied().collect())
+            .unwrap_or_default();
+
+        for (from, r) in &self.rates {
+            if r.contains_key(&currency) && !neighbours.contains(from) {
+                neighbours.push(*from);
+            }
+        }
+
+        neighbours
+    }
+
+    // Mid rate along one recorded pair, in either direction
+    pub fn mid(&self, from: Currency, to: Currency) -> Option<f64> {
+        self.direct(from, to).map(|(_, mid)| mid)
+    }
+
+    // Loops over directly recorded pairs that a customer could exchange around for a profit
+    pub fn arbitrage_loops(&self) -> Vec<ArbitrageLoop> {
+        let mut currencies: Vec<Currency> = self.pairs().flat_map(|(from, to, _)| [from, to]).collect();
+        let mut candidates: Vec<Vec<Currency>> = Vec::new();
+
+        currencies.sort();
+        currencies.dedup();
+        for (i, &a) in currencies.iter().enumerate() {
+            for (j, &b) in currencies.iter().enumerate().skip(i + 1) {
+                // Going there and back can only gain when both directions were recorded as separate pairs
+                if self.get(a, b).is_some() && self.get(b, a).is_some() {
+                    candidates.push(vec![a, b, a]);
+                }
+                for &c in currencies.iter().skip(j + 1) {
+                    candidates.push(vec![a, b, c, a]);
+                    candidates.push(vec![a, c, b, a]);
+                }
+            }
+        }
+
+        candidates.into_iter()
+            .filter_map(|path| {
+                let result = path.windows(2)
+                    .map(|pair| self.direct(pair[0], pair[1]).map(|(rate, _)| rate))
+                    .product::<Option<f64>>()?;
+
+                (result > 1.0 + ARBITRAGE_TOLERANCE).then_some(ArbitrageLoop { path, result })
+            })
+            .collect()
+    }
+
+    pub fn pairs(&self) -> impl Iterator<Item = (Currency, Currency, PairRate)> + '_ {
+        self.rates.iter().flat_map(|(from, r)| r.iter().map(move |(to, rate)| (*from, *to, *rate)))
+    }
+}
@@ -0,0 +1,71 @@
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+
+pub const DATA_FILE: &str = "data/bank.json";
+
+pub enum StorageError {
+    Io(io::Error),
+    Corrupt(serde_json::Error),
+}
+
+impl fmt::Display for StorageError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StorageError::Io(e) => write!(f, "{}", e),
+            StorageError::Corrupt(e) => write!(f, "data file is corrupt ({})", e),
+        }
+    }
+}
+
+// JSON data file that is rewritten after every change and reloaded at startup
+pub struct Storage {
+    path: PathBuf,
+}
+
+impl Storage {
+    pub fn new(path: impl Into<PathBuf>) -> Storage {
+        Storage { path: path.into() }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    // Returns None when the data file does not exist yet
+    pub fn load<T: DeserializeOwned>(&self) -> Result<Option<T>, StorageError> {
+        let contents = match fs::read_to_string(&self.path) {
+            Ok(contents) => contents,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(StorageError::Io(e)),
+        };
+
+        serde_json::from_str(&contents).map(Some).map_err(StorageError::Corrupt)
+    }
+
+    // Writes to a temporary file first so a failed save never leaves a half-written data file
+    pub fn save<T: Serialize>(&self, value: &T) -> Result<(), StorageError> {
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent).map_err(StorageError::Io)?;
+        }
+
+        let contents = serde_json::to_string_pretty(value).map_err(StorageError::Corrupt)?;
+        let temp_path = self.path.with_extension("json.tmp");
+
+        fs::write(&temp_path, contents).map_err(StorageError::Io)?;
+        fs::rename(&temp_path, &self.path).map_err(StorageError::Io)
+    }
+
+    // Moves a corrupt data file aside so the app can start over without losing it
+    pub fn back_up_corrupt(&self) -> Result<PathBuf, StorageError> {
+        let backup_path = self.path.with_extension("json.corrupt");
+
+        fs::rename(&self.path, &backup_path).map_err(StorageError::Io)?;
+
+        Ok(backup_path)
+    }
+}
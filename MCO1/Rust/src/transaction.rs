@@ -0,0 +1,131 @@
+use chrono::{NaiveDate, NaiveDateTime};
+use serde::{Deserialize, Serialize};
+
+use crate::currency::Currency;
+use crate::money::Money;
+use crate::rates::PairRate;
+
+#[derive(Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub enum TransactionKind {
+    Deposit { amount: Money, before: Money, after: Money },
+    Withdrawal { amount: Money, before: Money, after: Money },
+    // Whole balance converted, from before accounts held a wallet per currency
+    Exchange { before: Money, after: Money, rate: f64 },
+    // The timestamp is when the new rate took effect; older records do not say who entered them
+    RateChange { from: Currency, to: Currency, before: Option<PairRate>, after: PairRate, #[serde(default)] entered_by: Option<String> },
+    // Only the net interest (gross minus tax) is credited to the balance
+    Interest { gross: Money, tax: Money, before: Money, after: Money },
+    // Each side of a transfer names the other account and the ID of the other side's transaction
+    TransferOut { amount: Money, before: Money, after: Money, to: u64, linked: u64 },
+    // The amount is in the receiving account's currency, converted at the rate from the sender's currency
+    TransferIn { amount: Money, before: Money, after: Money, from: u64, linked: u64, rate: f64 },
+    // Exchanges between wallets of one account are recorded as a linked debit and credit like transfers
+    // The amount debited includes the fee, which is not converted
+    ExchangeOut { amount: Money, before: Money, after: Money, to: Currency, linked: u64, #[serde(default)] fee: Option<Money> },
+    ExchangeIn { amount: Money, before: Money, after: Money, rate: f64, linked: u64 },
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct Transaction {
+    pub id: u64,
+    pub timestamp: NaiveDateTime,
+    // Rate changes are not tied to an account
+    pub account: Option<u64>,
+    pub kind: TransactionKind,
+}
+
+pub const CSV_HEADER: &str = "id,timestamp,type,amount,balance_before,currency_before,balance_after,currency_after,gross_interest,tax_withheld,counterparty_account,linked_transaction,fee";
+
+impl TransactionKind {
+    pub fn name(&self) -> &'static str {
+        match self {
+            TransactionKind::Deposit { .. } => "Deposit",
+            TransactionKind::Withdrawal { .. } => "Withdrawal",
+            TransactionKind::Exchange { .. } => "Exchange",
+            TransactionKind::RateChange { .. } => "Rate Change",
+            TransactionKind::Interest { .. } => "Interest",
+            TransactionKind::TransferOut { .. } => "Transfer Out",
+            TransactionKind::TransferIn { .. } => "Transfer In",
+            TransactionKind::ExchangeOut { .. } => "Exchange Out",
+            TransactionKind::ExchangeIn { .. } => "Exchange In",
+        }
+    }
+
+    // Amount moved and the balance before and after, for transactions that touch a balance
+    pub fn amounts(&self) -> Option<(Money, Money, Money)> {
+        match *self {
+            TransactionKind::Deposit { amount, before, after }
+            | TransactionKind::Withdrawal { amount, before, after }
+            | TransactionKind::TransferOut { amount, before, after, .. }
+            | TransactionKind::TransferIn { amount, before, after, .. }
+            | TransactionKind::ExchangeOut { amount, before, after, .. }
+            | TransactionKind::ExchangeIn { amount, before, after, .. } => Some((amount, before, after)),
+            TransactionKind::Exchange { before, after, .. } => Some((before, before, after)),
+            TransactionKind::Interest { before, after, .. } => Some((after - before, before, after)),
+            TransactionKind::RateChange { .. } => None,
+        }
+    }
+
+    // Gross interest and tax withheld, for interest postings
+    pub fn withholding(&self) -> Option<(Money, Money)> {
+        match *self {
+            TransactionKind::Interest { gross, tax, .. } => Some((gross, tax)),
+            _ => None,
+        }
+    }
+
+    // Fee charged on an exchange
+    pub fn fee(&self) -> Option<Money> {
+        match *self {
+            TransactionKind::ExchangeOut { fee, .. } => fee,
+            _ => None,
+        }
+    }
+
+    // Other account of a transfer
+    pub fn counterparty(&self) -> Option<u64> {
+        match *self {
+            TransactionKind::TransferOut { to, .. } => Some(to),
+            TransactionKind::TransferIn { from, .. } => Some(from),
+            _ => None,
+        }
+    }
+
+    // ID of the other side of a transfer or exchange
+    pub fn linked(&self) -> Option<u64> {
+        match *self {
+            TransactionKind::TransferOut { linked, .. }
+            | TransactionKind::TransferIn { linked, .. }
+            | TransactionKind::ExchangeOut { linked, .. }
+            | TransactionKind::ExchangeIn { linked, .. } => Some(linked),
+            _ => None,
+        }
+    }
+}
+
+impl Transaction {
+    pub fn is_within(&self, start: Option<NaiveDate>, end: Option<NaiveDate>) -> bool {
+        let date = self.timestamp.date();
+
+        start.is_none_or(|start| date >= start) && end.is_none_or(|end| date <= end)
+    }
+
+    pub fn to_csv(&self) -> String {
+        let withholding = match self.kind.withholding() {
+            Some((gross, tax)) => format!("{gross},{tax}"),
+            None => ",".to_string(),
+        };
+        let linked = format!("{},{},{}",
+            self.kind.counterparty().map_or(String::new(), |a| a.to_string()),
+            self.kind.linked().map_or(String::new(), |id| id.to_string()),
+            self.kind.fee().map_or(String::new(), |fee| fee.to_string()));
+
+        match self.kind.amounts() {
+            Some((amount, before, after)) => format!("{},{},{},{},{},{},{},{},{},{}",
+                self.id, self.timestamp.format("%Y-%m-%d %H:%M:%S"), self.kind.name(),
+                amount, before, before.currency(), after, after.currency(), withholding, linked),
+            None => format!("{},{},{},,,,,,{},{}", self.id, self.timestamp.format("%Y-%m-%d %H:%M:%S"), self.kind.name(), withholding, linked),
+        }
+    }
+}
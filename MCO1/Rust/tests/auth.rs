@@ -0,0 +1,65 @@
+use std::time::{Duration, Instant};
+
+use chrono::{NaiveDate, NaiveDateTime, TimeDelta};
+
+use mco1::auth::{Credentials, LOCKOUT_MINUTES, SESSION_TIMEOUT, Session};
+use mco1::error::BankError;
+
+fn noon() -> NaiveDateTime {
+    NaiveDate::from_ymd_opt(2025, 3, 3).unwrap().and_hms_opt(12, 0, 0).unwrap()
+}
+
+#[test]
+fn pins_must_be_four_to_six_digits() {
+    for pin in ["123", "1234567", "12a4", ""] {
+        assert!(matches!(Credentials::new(pin), Err(BankError::InvalidPin)), "{pin:?} was accepted");
+    }
+    assert!(Credentials::new("0000").is_ok());
+    assert!(Credentials::new("123456").is_ok());
+}
+
+#[test]
+fn the_same_pin_is_salted_differently() {
+    let first = serde_json::to_value(Credentials::new("1234").unwrap()).unwrap();
+    let second = serde_json::to_value(Credentials::new("1234").unwrap()).unwrap();
+
+    assert_ne!(first["salt"], second["salt"]);
+    assert_ne!(first["hash"], second["hash"]);
+    assert!(!first.to_string().contains("1234"));
+}
+
+#[test]
+fn wrong_pins_lock_the_account_for_a_while() {
+    let mut credentials = Credentials::new("1234").unwrap();
+    let now = noon();
+    let until = now + TimeDelta::minutes(LOCKOUT_MINUTES);
+
+    assert_eq!(credentials.verify("1111", now), Err(BankError::WrongPin { attempts_left: 2 }));
+    assert_eq!(credentials.verify("2222", now), Err(BankError::WrongPin { attempts_left: 1 }));
+    assert_eq!(credentials.verify("3333", now), Err(BankError::AccountLocked { until }));
+    // Even the right PIN is refused until the lockout ends
+    assert_eq!(credentials.verify("1234", until - TimeDelta::seconds(1)), Err(BankError::AccountLocked { until }));
+    assert_eq!(credentials.verify("1234", until), Ok(()));
+}
+
+#[test]
+fn a_right_pin_clears_the_wrong_attempts() {
+    let mut credentials = Credentials::new("1234").unwrap();
+
+    credentials.verify("1111", noon()).unwrap_err();
+    credentials.verify("2222", noon()).unwrap_err();
+    credentials.verify("1234", noon()).unwrap();
+
+    assert_eq!(credentials.verify("1111", noon()), Err(BankError::WrongPin { attempts_left: 2 }));
+}
+
+#[test]
+fn sessions_expire_after_inactivity() {
+    let start = Instant::now();
+    let mut session = Session::new(100_001, start);
+
+    assert!(!session.expired(start + SESSION_TIMEOUT - Duration::from_secs(1)));
+    session.touch(start + Duration::from_secs(60));
+    assert!(!session.expired(start + SESSION_TIMEOUT));
+    assert!(session.expired(start + Duration::from_secs(60) + SESSION_TIMEOUT));
+}
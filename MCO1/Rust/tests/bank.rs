@@ -0,0 +1,331 @@
+use mco1::account::Bank;
+use mco1::auth::Role;
+use mco1::currency::{Currency, CurrencyInfo, CurrencyRegistry};
+use mco1::error::{Action, BankError};
+use mco1::fees::FeeRule;
+use mco1::money::Money;
+use mco1::import::RateRow;
+use mco1::rates::PairRate;
+
+fn usd(registry: &CurrencyRegistry) -> &CurrencyInfo {
+    registry.get(Currency::new("USD").unwrap()).unwrap()
+}
+
+// Bank with one account holding the given amount of pesos
+fn funded_bank(registry: &CurrencyRegistry, pesos: i64) -> (Bank, u64) {
+    let mut bank = Bank::new();
+    let number = bank.open("Juan Dela Cruz".to_string(), "1234", registry.base()).unwrap();
+
+    bank.deposit(number, Money::from_major(pesos, registry.base()), registry.base()).unwrap();
+    (bank, number)
+}
+
+#[test]
+fn open_rejects_names_with_digits_or_symbols() {
+    let registry = CurrencyRegistry::new();
+    let mut bank = Bank::new();
+
+    assert_eq!(bank.open("Juan 2".to_string(), "1234", registry.base()), Err(BankError::InvalidName));
+    assert_eq!(bank.open(String::new(), "1234", registry.base()), Err(BankError::InvalidName));
+    assert_eq!(bank.open("Juan".to_string(), "1234", registry.base()), Ok(100_001));
+    assert_eq!(bank.open("Maria".to_string(), "1234", registry.base()), Ok(100_002));
+}
+
+#[test]
+fn currencies_missing_from_the_registry_or_with_other_minor_units_are_reported() {
+    let mut registry = CurrencyRegistry::new();
+    registry.add("KRW", "South Korean Won", "₩", 0).unwrap();
+    let won = registry.get(Currency::new("KRW").unwrap()).unwrap().clone();
+    let (mut bank, number) = funded_bank(&registry, 100);
+    bank.deposit(number, Money::from_major(5000, &won), &won).unwrap();
+    assert_eq!(bank.check_currencies(&registry), Ok(()));
+
+    assert_eq!(bank.check_currencies(&CurrencyRegistry::new()), Err("currency KRW is not registered".to_string()));
+
+    let mut changed = CurrencyRegistry::new();
+    changed.add("KRW", "South Korean Won", "₩", 2).unwrap();
+    assert_eq!(
+        bank.check_currencies(&changed),
+        Err("KRW amounts have 0 minor units but the registry has 2".to_string())
+    );
+}
+
+#[test]
+fn deposit_respects_the_balance_cap() {
+    let registry = CurrencyRegistry::new();
+    let (mut bank, number) = funded_bank(&registry, 999_000);
+    let php = registry.base();
+
+    assert_eq!(bank.deposit(number, Money::from_major(0, php), php), Err(BankError::NotPositive(Action::Deposit)));
+    assert_eq!(bank.deposit(number, Money::from_major(1_001, php), php),
+        Err(BankError::CapExceeded { account: number, currency: Currency::PHP, action: Action::Deposit }));
+    // Too large to add to the balance at all, rather than wrapping around to a negative balance
+    assert_eq!(bank.deposit(number, Money::parse("92233720368547758.07", php).unwrap(), php),
+        Err(BankError::CapExceeded { account: number, currency: Currency::PHP, action: Action::Deposit }));
+    assert!(bank.deposit(number, Money::from_major(1_000, php), php).is_ok());
+    assert_eq!(bank.get(number).unwrap().balance(php), Money::from_major(1_000_000, php));
+}
+
+#[test]
+fn withdraw_cannot_overdraw() {
+    let registry = CurrencyRegistry::new();
+    let (mut bank, number) = funded_bank(&registry, 500);
+    let php = registry.base();
+
+    assert_eq!(bank.withdraw(number, Money::from_major(501, php)),
+        Err(BankError::InsufficientFunds { currency: Currency::PHP, action: Action::Withdrawal }));
+    assert!(matches!(bank.withdraw(number, Money::from_major(1, usd(&registry))), Err(BankError::InsufficientFunds { .. })));
+    assert!(bank.withdraw(number, Money::from_major(500, php)).is_ok());
+    assert!(!bank.get(number).unwrap().has_funds());
+}
+
+#[test]
+fn exchange_charges_the_fee_before_converting() {
+    let registry = CurrencyRegistry::new();
+    let (mut bank, number) = funded_bank(&registry, 1_000);
+    let (php, usd) = (registry.base(), usd(&registry));
+
+    bank.fees.set(Currency::PHP, FeeRule::Flat(Money::from_major(100, php)));
+    let quote = bank.quote_exchange(number, Money::from_major(1_000, php), usd).unwrap();
+
+    assert_eq!(quote.fee, Money::from_major(100, php));
+    assert_eq!(quote.credit, Money::parse("15.30", usd).unwrap());
+
+    bank.exchange(&quote, usd).unwrap();
+    let account = bank.get(number).unwrap();
+    assert_eq!(account.balance(php), Money::zero(php));
+    assert_eq!(account.balance(usd), Money::parse("15.30", usd).unwrap());
+}
+
+#[test]
+fn quote_expires_when_rates_change() {
+    let registry = CurrencyRegistry::new();
+    let (mut bank, number) = funded_bank(&registry, 1_000);
+    let (php, usd) = (registry.base(), usd(&registry));
+
+    let quote = bank.quote_exchange(number, Money::from_major(100, php), usd).unwrap();
+    bank.set_rate(Currency::PHP, usd.code, PairRate { buy: 0.018, sell: 0.018 }, "Test".to_string());
+
+    assert!(matches!(bank.exchange(&quote, usd), Err(BankError::QuoteExpired)));
+    assert_eq!(bank.get(number).unwrap().balance(php), Money::from_major(1_000, php));
+}
+
+#[test]
+fn daily_interest_keeps_the_rounding_remainder_between_postings() {
+    let registry = CurrencyRegistry::new();
+
+    // A day of 5% on ₱100 is ₱0.0137 and on ₱930 is ₱0.1274; posting a rounded amount and dropping
+    // the rest would pay ₱0.01 and ₱0.13 a day
+    for pesos in [100, 930] {
+        let (mut bank, number) = funded_bank(&registry, pesos);
+        bank.withholding_tax_rate = 0.0;
+        let start = bank.business_date();
+
+        while (bank.business_date() - start).num_days() < 28 {
+            bank.end_of_day(&registry);
+        }
+
+        let days = (bank.business_date() - start).num_days() as i32;
+        let expected = pesos as f64 * (1.0_f64 + 0.05 / 365.0).powi(days);
+        let balance = bank.get(number).unwrap().balance(registry.base()).to_f64();
+        assert!((balance - expected).abs() <= 0.01, "₱{pesos} over {days} days: {balance} is not {expected}");
+    }
+}
+
+#[test]
+fn transfer_links_the_debit_and_credit() {
+    let registry = CurrencyRegistry::new();
+    let (mut bank, from) = funded_bank(&registry, 1_000);
+    let php = registry.base();
+    let to = bank.open("Maria".to_string(), "1234", php).unwrap();
+
+    assert_eq!(bank.transfer(from, from, Money::from_major(10, php), php), Err(BankError::SameAccount));
+    assert_eq!(bank.transfer(from, 999_999, Money::from_major(10, php), php), Err(BankError::AccountNotFound(999_999)));
+    let (debit_id, credit_id) = bank.transfer(from, to, Money::from_major(250, php), php).unwrap();
+
+    assert_eq!(bank.transaction(debit_id).unwrap().kind.linked(), Some(credit_id));
+    assert_eq!(bank.transaction(credit_id).unwrap().kind.linked(), Some(debit_id));
+    assert_eq!(bank.get(from).unwrap().balance(php), Money::from_major(750, php));
+    assert_eq!(bank.get(to).unwrap().balance(php), Money::from_major(250, php));
+}
+
+#[test]
+fn close_refuses_accounts_with_money_left() {
+    let registry = CurrencyRegistry::new();
+    let php = registry.base();
+    let (mut bank, _) = funded_bank(&registry, 10);
+    bank.open("Pedro".to_string(), "1234", php).unwrap();
+    let number = bank.open("Maria".to_string(), "1234", php).unwrap();
+
+    bank.deposit(number, Money::from_major(10, php), php).unwrap();
+    assert!(matches!(bank.close(number), Err(BankError::HasFunds)));
+    bank.withdraw(number, Money::from_major(10, php)).unwrap();
+    assert!(bank.close(number).is_ok());
+    assert!(matches!(bank.close(number), Err(BankError::AccountNotFound(_))));
+}
+
+#[test]
+fn stale_rates_are_refused_with_their_own_exit_code() {
+    let registry = CurrencyRegistry::new();
+    let (mut bank, number) = funded_bank(&registry, 100);
+    let php = registry.base();
+
+    bank.max_rate_age_hours = Some(1);
+    let e = bank.quote_exchange(number, Money::from_major(100, php), usd(&registry)).err().unwrap();
+
+    assert!(matches!(e, BankError::StaleRate { hours: 1, .. }));
+    assert_ne!(e.exit_code(), BankError::NoFunds.exit_code());
+
+    // Business days passing in end-of-day processing do not make a rate entered a moment ago stale
+    bank.set_rate(Currency::PHP, usd(&registry).code, PairRate { buy: 0.017, sell: 0.017 }, "Test".to_string());
+    for _ in 0..5 {
+        bank.end_of_day(&registry);
+    }
+    assert!(bank.quote_exchange(number, Money::from_major(50, php), usd(&registry)).is_ok());
+}
+
+#[test]
+fn a_direct_rate_out_of_line_with_the_peso_rates_is_arbitrage() {
+    let registry = CurrencyRegistry::new();
+    let bank = Bank::new();
+    let (usd, jpy) = (usd(&registry).code, Currency::new("JPY").unwrap());
+
+    // Through pesos, 1 USD buys about 153 JPY
+    assert!(bank.rate_warnings(usd, jpy, PairRate { buy: 152.0, sell: 154.0 }).is_empty());
+    assert!(!bank.rate_warnings(usd, jpy, PairRate { buy: 170.0, sell: 171.0 }).is_empty());
+}
+
+#[test]
+fn the_first_two_accounts_administer_the_bank_and_promotions_need_both() {
+    let registry = CurrencyRegistry::new();
+    let mut bank = Bank::new();
+    let admin = bank.open("Juan".to_string(), "1234", registry.base()).unwrap();
+    let second = bank.open("Maria".to_string(), "1234", registry.base()).unwrap();
+    let teller = bank.open("Pedro".to_string(), "1234", registry.base()).unwrap();
+
+    assert_eq!(bank.get(second).unwrap().role, Role::Administrator);
+    assert_eq!(bank.get(teller).unwrap().role, Role::Teller);
+    assert_eq!(bank.set_role(teller, teller, Role::Administrator), Err(BankError::AdministratorOnly));
+    assert_eq!(bank.set_role(admin, second, Role::Teller), Err(BankError::LastAdministrator));
+    assert!(matches!(bank.close(admin), Err(BankError::LastAdministrator)));
+
+    // One administrator asking is not enough, however often they ask
+    assert_eq!(bank.set_role(admin, teller, Role::Administrator), Ok(false));
+    assert_eq!(bank.set_role(admin, teller, Role::Administrator), Err(BankError::SecondApproverRequired));
+    assert_eq!(bank.get(teller).unwrap().role, Role::Teller);
+    assert_eq!(bank.pending_promotion(teller), Some(admin));
+
+    assert_eq!(bank.set_role(second, teller, Role::Administrator), Ok(true));
+    assert_eq!(bank.get(teller).unwrap().role, Role::Administrator);
+    assert_eq!(bank.set_role(teller, admin, Role::Teller), Ok(true));
+}
+
+#[test]
+fn accounts_saved_before_pins_and_roles_wait_for_new_administrators() {
+    let registry = CurrencyRegistry::new();
+    let mut bank = Bank::new();
+    let oldest = bank.open("Juan".to_string(), "1234", registry.base()).unwrap();
+    bank.open("Maria".to_string(), "1234", registry.base()).unwrap();
+
+    let mut saved = serde_json::to_value(&bank).unwrap();
+    for account in saved["accounts"].as_object_mut().unwrap().values_mut() {
+        account.as_object_mut().unwrap().remove("role");
+        account.as_object_mut().unwrap().remove("pin");
+    }
+    let mut bank: Bank = serde_json::from_value(saved).unwrap();
+
+    // Nobody has proved they own the saved accounts, so none of them becomes an administrator or sets its own PIN
+    assert!(bank.setting_up());
+    assert_eq!(bank.log_in(oldest, "5555"), Err(BankError::NoPin(oldest)));
+    let newest = bank.open("Pedro".to_string(), "1234", registry.base()).unwrap();
+    assert_eq!(bank.get(newest).unwrap().role, Role::Teller);
+    assert_eq!(bank.set_pin(newest, oldest, "5555"), Err(BankError::AdministratorOnly));
+
+    let admin = bank.set_up_administrator("Ana".to_string(), "2468", registry.base()).unwrap();
+    assert_eq!(bank.get(admin).unwrap().role, Role::Administrator);
+    bank.set_up_administrator("Jose".to_string(), "2468", registry.base()).unwrap();
+    assert!(!bank.setting_up());
+    assert!(matches!(bank.set_up_administrator("Luis".to_string(), "2468", registry.base()), Err(BankError::AdministratorExists)));
+
+    bank.set_pin(admin, oldest, "5555").unwrap();
+    assert_eq!(bank.log_in(oldest, "5555"), Ok(()));
+    assert_eq!(bank.get(oldest).unwrap().role, Role::Teller);
+}
+
+#[test]
+fn large_rate_changes_wait_for_a_second_administrator() {
+    let registry = CurrencyRegistry::new();
+    let mut bank = Bank::new();
+    let maker = bank.open("Juan".to_string(), "1234", registry.base()).unwrap();
+    let checker = bank.open("Maria".to_string(), "1234", registry.base()).unwrap();
+    let teller = bank.open("Pedro".to_string(), "1234", registry.base()).unwrap();
+    let usd = usd(&registry).code;
+    let small = RateRow { line: 1, from: Currency::PHP, to: usd, rate: PairRate { buy: 0.0172, sell: 0.0172 } };
+    let large = RateRow { line: 2, from: Currency::PHP, to: usd, rate: PairRate { buy: 0.02, sell: 0.02 } };
+
+    assert!(matches!(bank.apply_rates(std::slice::from_ref(&small), "Test", teller), Err(BankError::AdministratorOnly)));
+    assert_eq!(bank.apply_rates(std::slice::from_ref(&small), "Test", maker).unwrap().changed, 1);
+    let update = bank.apply_rates(std::slice::from_ref(&large), "Test", maker).unwrap();
+    assert_eq!((update.changed, update.pending.len()), (0, 1));
+    assert_eq!(bank.rates.get(Currency::PHP, usd), Some(small.rate));
+
+    let id = update.pending[0];
+    assert_eq!(bank.approve_rate(id, maker), Err(BankError::SecondApproverRequired));
+    assert!(bank.approve_rate(id, checker).is_ok());
+    assert_eq!(bank.rates.get(Currency::PHP, usd), Some(large.rate));
+    assert!(bank.pending_rates().is_empty());
+}
+
+#[test]
+fn steps_under_the_threshold_cannot_add_up_past_it() {
+    let registry = CurrencyRegistry::new();
+    let mut bank = Bank::new();
+    let maker = bank.open("Juan".to_string(), "1234", registry.base()).unwrap();
+    bank.open("Maria".to_string(), "1234", registry.base()).unwrap();
+    let usd = usd(&registry).code;
+    // Each step moves the 0.017 rate by less than 5%, but the second ends up 9% from it
+    let first = RateRow { line: 1, from: Currency::PHP, to: usd, rate: PairRate { buy: 0.0177, sell: 0.0177 } };
+    let second = RateRow { line: 1, from: Currency::PHP, to: usd, rate: PairRate { buy: 0.01853, sell: 0.01853 } };
+
+    assert_eq!(bank.apply_rates(std::slice::from_ref(&first), "Test", maker).unwrap().changed, 1);
+    let update = bank.apply_rates(std::slice::from_ref(&second), "Test", maker).unwrap();
+    assert_eq!((update.changed, update.pending.len()), (0, 1));
+    assert_eq!(bank.rates.get(Currency::PHP, usd), Some(first.rate));
+}
+
+#[test]
+fn reversed_and_new_cross_pairs_cannot_skip_approval() {
+    let registry = CurrencyRegistry::new();
+    let mut bank = Bank::new();
+    let maker = bank.open("Juan".to_string(), "1234", registry.base()).unwrap();
+    let (usd, jpy) = (usd(&registry).code, Currency::new("JPY").unwrap());
+    // PHP/USD is recorded, so USD/PHP would be a second rate for the same pair
+    let reversed = RateRow { line: 1, from: usd, to: Currency::PHP, rate: PairRate { buy: 50.0, sell: 50.0 } };
+    // Through pesos, 1 USD buys about 153 JPY; a direct USD/JPY rate would replace that
+    let cross = RateRow { line: 2, from: usd, to: jpy, rate: PairRate { buy: 152.0, sell: 154.0 } };
+
+    let update = bank.apply_rates(&[reversed, cross], "Test", maker).unwrap();
+    assert_eq!((update.changed, update.pending.len(), update.rejected.len()), (0, 1, 1));
+    assert_eq!(update.rejected[0].line, 1);
+    assert_eq!(bank.rates.get(usd, Currency::PHP), None);
+    assert_eq!(bank.rates.get(usd, jpy), None);
+
+    // The reversed pair is compared with the recorded PHP/USD rate of about 58.82 pesos to the dollar
+    assert!(bank.needs_approval(usd, Currency::PHP, PairRate { buy: 50.0, sell: 50.0 }));
+    assert!(!bank.needs_approval(usd, Currency::PHP, PairRate { buy: 58.5, sell: 58.5 }));
+}
+
+#[test]
+fn the_approval_threshold_cannot_be_raised_past_the_cap() {
+    let registry = CurrencyRegistry::new();
+    let mut bank = Bank::new();
+    let admin = bank.open("Juan".to_string(), "1234", registry.base()).unwrap();
+    bank.open("Maria".to_string(), "1234", registry.base()).unwrap();
+    let teller = bank.open("Pedro".to_string(), "1234", registry.base()).unwrap();
+
+    assert_eq!(bank.set_approval_threshold(teller, 0.08), Err(BankError::AdministratorOnly));
+    assert_eq!(bank.set_approval_threshold(admin, 0.5), Err(BankError::ThresholdOutOfRange));
+    assert_eq!(bank.set_approval_threshold(admin, 0.0), Err(BankError::ThresholdOutOfRange));
+    assert_eq!(bank.set_approval_threshold(admin, 0.08), Ok(()));
+    assert_eq!(bank.approval_threshold(), 0.08);
+}
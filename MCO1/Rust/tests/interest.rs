@@ -0,0 +1,44 @@
+use chrono::NaiveDate;
+
+use mco1::interest::{Compounding, DayCount};
+
+fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(year, month, day).unwrap()
+}
+
+fn assert_close(actual: f64, expected: f64) {
+    assert!((actual - expected).abs() < 1e-12, "{actual} is not {expected}");
+}
+
+#[test]
+fn actual_365_and_actual_360_divide_the_actual_days() {
+    let (start, end) = (date(2024, 1, 1), date(2024, 4, 1));
+
+    assert_close(DayCount::Actual365.year_fraction(start, end), 91.0 / 365.0);
+    assert_close(DayCount::Actual360.year_fraction(start, end), 91.0 / 360.0);
+}
+
+#[test]
+fn actual_actual_splits_the_days_at_a_leap_year_boundary() {
+    // 31 days of 2023 and 60 days of the leap year 2024
+    assert_close(DayCount::ActualActual.year_fraction(date(2023, 12, 1), date(2024, 3, 1)), 31.0 / 365.0 + 60.0 / 366.0);
+    assert_close(DayCount::ActualActual.year_fraction(date(2024, 1, 1), date(2025, 1, 1)), 1.0);
+}
+
+#[test]
+fn thirty_360_treats_the_31st_as_the_30th() {
+    // A 31st start counts as the 30th, and so does a 31st end after it
+    assert_close(DayCount::Thirty360.year_fraction(date(2024, 1, 31), date(2024, 3, 31)), 60.0 / 360.0);
+    // A 31st end keeps its day when the start is before the 30th
+    assert_close(DayCount::Thirty360.year_fraction(date(2024, 1, 15), date(2024, 3, 31)), 76.0 / 360.0);
+    assert_close(DayCount::Thirty360.year_fraction(date(2024, 1, 31), date(2024, 2, 29)), 29.0 / 360.0);
+}
+
+#[test]
+fn monthly_period_ends_do_not_drift_after_a_short_month() {
+    let start = date(2024, 1, 31);
+
+    assert_eq!(Compounding::Monthly.period_end(start, 1), date(2024, 2, 29));
+    assert_eq!(Compounding::Monthly.period_end(start, 2), date(2024, 3, 31));
+    assert_eq!(Compounding::Quarterly.period_end(start, 1), date(2024, 4, 30));
+}
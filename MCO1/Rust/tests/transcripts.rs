@@ -0,0 +1,68 @@
+// Replays recorded menu sessions and compares the output with the transcripts in tests/transcripts.
+// Each session has NAME.in with the answers typed and NAME.out with everything the menu printed.
+// Run with UPDATE_TRANSCRIPTS=1 to rewrite the .out files after an intended change to the menu.
+
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+use std::process;
+
+use chrono::Local;
+
+use mco1::menu::{self, Console};
+use mco1::storage::Storage;
+
+// Stands in for the business date, which starts as the day the test runs
+const DATE_PLACEHOLDER: &str = "{BUSINESS_DATE}";
+
+fn replay(name: &str) {
+    let transcripts = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/transcripts");
+    let data_dir = env::temp_dir().join(format!("mco1-transcript-{name}-{}", process::id()));
+    let input = fs::read_to_string(transcripts.join(format!("{name}.in"))).unwrap();
+    let expected_path = transcripts.join(format!("{name}.out"));
+
+    let _ = fs::remove_dir_all(&data_dir);
+    let mut console = Console::new(input.as_bytes(), Vec::new());
+    console.echo = true;
+    menu::run(&mut console, &Storage::new(data_dir.join("bank.json")), &Storage::new(data_dir.join("currencies.json")));
+    let _ = fs::remove_dir_all(&data_dir);
+
+    let output = String::from_utf8(console.into_writer()).unwrap()
+        .replace(&Local::now().date_naive().to_string(), DATE_PLACEHOLDER);
+
+    if env::var_os("UPDATE_TRANSCRIPTS").is_some() {
+        fs::write(&expected_path, &output).unwrap();
+    } else {
+        assert_eq!(output, fs::read_to_string(&expected_path).unwrap(), "session {name} no longer matches its transcript");
+    }
+}
+
+#[test]
+fn register_deposit_and_withdraw() {
+    replay("register_deposit_withdraw");
+}
+
+#[test]
+fn currency_exchange() {
+    replay("currency_exchange");
+}
+
+#[test]
+fn operations_are_refused_until_allowed() {
+    replay("refused_operations");
+}
+
+#[test]
+fn session_ends_when_input_runs_out() {
+    replay("input_ended");
+}
+
+#[test]
+fn log_in_with_a_pin() {
+    replay("login");
+}
+
+#[test]
+fn large_rate_changes_need_a_second_administrator() {
+    replay("rate_approval");
+}